@@ -0,0 +1,54 @@
+use bevy::ecs::{component::Component, schedule::SystemStage, world::World};
+use bevy::prelude::{Reflect, Time};
+use criterion::{criterion_group, criterion_main, Criterion};
+use derive_more::{Add, Sub};
+use leafwing_abilities::resources::{regen_resource, ResourcePool, ResourceType};
+
+criterion_group!(benches, regen_idle_pools);
+criterion_main!(benches);
+
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Add, Sub, Debug)]
+struct Mana(u32);
+
+impl ResourceType for Mana {
+    const ZERO: Self = Mana(0);
+    const LOGICAL_MAX: Self = Mana(u32::MAX);
+}
+
+impl From<f32> for Mana {
+    fn from(value: f32) -> Self {
+        Mana(value as u32)
+    }
+}
+
+impl From<Mana> for f32 {
+    fn from(mana: Mana) -> f32 {
+        mana.0 as f32
+    }
+}
+
+/// Most units in large battles have pools which never regenerate
+fn regen_idle_pools(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("regen_idle_pools");
+    group.warm_up_time(std::time::Duration::from_millis(500));
+    group.measurement_time(std::time::Duration::from_secs(4));
+
+    for entity_count in (1..5).map(|i| i * 2 * 1000) {
+        group.bench_function(format!("{}_entities", entity_count), |bencher| {
+            let mut world = World::default();
+            world.insert_resource(Time::default());
+            for i in 0..entity_count {
+                // One in every hundred pools regenerates
+                let regen_rate = if i % 100 == 0 { Mana(1) } else { Mana::ZERO };
+                world
+                    .spawn()
+                    .insert(ResourcePool::new(Mana(50), Mana(100), regen_rate));
+            }
+
+            let mut stage = SystemStage::single(regen_resource::<Mana>);
+            bencher.iter(|| stage.run(&mut world));
+        });
+    }
+
+    group.finish();
+}
@@ -0,0 +1,62 @@
+//! Combo points are an integer resource, which regenerates one whole point at a time using `TickRegen`
+
+use bevy::prelude::*;
+use core::time::Duration;
+use derive_more::{Add, Sub};
+use leafwing_abilities::abilities::AbilitiesPlugin;
+use leafwing_abilities::resources::{ResourcePool, ResourcePoolExt, ResourceType, TickRegen};
+use leafwing_input_manager::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugin(InputManagerPlugin::<Action>::default())
+        .add_plugin(AbilitiesPlugin::<Action>::default())
+        .add_resource_pool::<ComboPoints>()
+        .add_startup_system(spawn_rogue)
+        .add_system(report_combo_points)
+        .run();
+}
+
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+enum Action {
+    Eviscerate,
+}
+
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Add, Sub, Debug)]
+struct ComboPoints(u8);
+
+impl ResourceType for ComboPoints {
+    const ZERO: Self = ComboPoints(0);
+    const LOGICAL_MAX: Self = ComboPoints(5);
+}
+
+impl From<f32> for ComboPoints {
+    fn from(value: f32) -> Self {
+        ComboPoints(value as u8)
+    }
+}
+
+impl From<ComboPoints> for f32 {
+    fn from(combo_points: ComboPoints) -> f32 {
+        combo_points.0 as f32
+    }
+}
+
+fn spawn_rogue(mut commands: Commands) {
+    // One combo point is gained every second, up to a maximum of five
+    commands
+        .spawn()
+        .insert(ResourcePool::empty(ComboPoints(5), ComboPoints(1)))
+        .insert(TickRegen::<ComboPoints>::new(Duration::from_secs(1)));
+}
+
+fn report_combo_points(query: Query<&ResourcePool<ComboPoints>>, mut last_seen: Local<u8>) {
+    for combo_points in query.iter() {
+        let current = combo_points.current().0;
+        if current != *last_seen {
+            println!("Combo points: {}", current);
+            *last_seen = current;
+        }
+    }
+}
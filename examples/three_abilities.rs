@@ -0,0 +1,96 @@
+//! Spawns a unit with three abilities, using `SpawnAbilityExt` to build each ability entity
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use derive_more::{Add, Sub};
+use leafwing_abilities::abilities::ability_mapping::{
+    AbilityMap, InputControlled, SimpleAbilityMap,
+};
+use leafwing_abilities::abilities::cooldowns::Cooldown;
+use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+use leafwing_abilities::abilities::{Abilities, AbilitiesPlugin, AbilityCategory};
+use leafwing_abilities::resources::{ResourcePool, ResourcePoolExt, ResourceType};
+use leafwing_input_manager::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .add_plugin(InputManagerPlugin::<Action>::default())
+        .add_plugin(AbilitiesPlugin::<Action>::default())
+        .add_resource_pool::<Mana>()
+        .add_startup_system(spawn_wizard)
+        .run();
+}
+
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+enum Action {
+    Fireball,
+    Blink,
+    Meteor,
+}
+
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Add, Sub, Debug)]
+struct Mana(u32);
+
+impl ResourceType for Mana {
+    const ZERO: Self = Mana(0);
+    const LOGICAL_MAX: Self = Mana(1000);
+}
+
+impl From<f32> for Mana {
+    fn from(value: f32) -> Self {
+        Mana(value as u32)
+    }
+}
+
+impl From<Mana> for f32 {
+    fn from(mana: Mana) -> f32 {
+        mana.0 as f32
+    }
+}
+
+#[derive(Component)]
+struct Fireball;
+
+#[derive(Component)]
+struct Blink;
+
+#[derive(Component)]
+struct Meteor;
+
+fn spawn_wizard(mut commands: Commands) {
+    let fireball = commands
+        .spawn_ability()
+        .with(Fireball)
+        .with_cooldown(Cooldown::new(1.0))
+        .with_cost(Mana(10))
+        .id();
+
+    // Blinking does not interrupt casting
+    let blink = commands
+        .spawn_ability()
+        .with(Blink)
+        .with_cooldown(Cooldown::new_with_charges(8.0, 2))
+        .with_category(AbilityCategory("movement"))
+        .id();
+
+    let meteor = commands
+        .spawn_ability()
+        .with(Meteor)
+        .with_cooldown(Cooldown::new_unavailable(60.0))
+        .with_cost(Mana(80))
+        .id();
+
+    let mut map = HashMap::default();
+    map.insert(Action::Fireball, fireball);
+    map.insert(Action::Blink, blink);
+    map.insert(Action::Meteor, meteor);
+    let ability_map = AbilityMap::new(SimpleAbilityMap::new(map));
+
+    commands
+        .spawn()
+        .insert(Abilities::from_ability_map(&ability_map))
+        .insert(ability_map)
+        .insert(ResourcePool::new(Mana(100), Mana(100), Mana(5)))
+        .insert(InputControlled);
+}
@@ -1,6 +1,6 @@
 /// All combat actions other than movement are abilities.
 /// Abilities have several important design constraints:
-///  - only one ability may be used at once
+///  - only one ability of each `AbilityCategory` may be used at once
 ///  - abilities are stronger when used in time with the beat
 ///  - input actions do not map one-to-one with abilities: chords may be required
 ///  - the player (but not enemies) should use the first action input if there are conflicts
@@ -14,377 +14,3478 @@
 /// - data such as cooldown, resource cost and so on are stored on the ability entity in the form of components
 /// - these are updated and managed in broad systems which perform standard logic like ticking down cooldowns
 /// - each unit tracks which abilities it can and is using in its `Abilities` component
-/// - abilties may only be used if their `Usable` component == true
+/// - abilties may only be used if their `Usable` component is `UsabilityReason::Ready`
 /// - systems that cause abilities to take effects are always enabled, but rely on the presence of the `JustStarted` component to know when to take effect
 use bevy::prelude::*;
 
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 use core::hash::Hash;
+use core::marker::PhantomData;
+use core::time::Duration;
+use leafwing_input_manager::{plugin::InputManagerSystem, prelude::Actionlike};
+use std::borrow::Cow;
 
-use crate::input::{ActionState, InputLabel};
-use ability_mapping::{AbilityInputMap, NullAbilityMap};
-use usability::Usable;
+use ability_mapping::AbilityMap;
+use usability::{UsabilityReason, Usable};
 
-pub struct AbilitiesPlugin;
+/// Manages the abilities of units, whose inputs are described by the `Actionlike` type `A`
+///
+/// By default, systems are added to the `CoreStage`s described by `AbilityStages::default`.
+/// Use `AbilitiesPlugin::new` to place them in your own stages, such as a fixed timestep stage.
+pub struct AbilitiesPlugin<A: Actionlike, S: StageLabel + Clone = CoreStage> {
+    pub stages: AbilityStages<S>,
+    /// If set, ability systems advance by this constant step, rather than the frame delta
+    pub timestep: Option<Duration>,
+    _phantom: PhantomData<A>,
+}
+
+impl<A: Actionlike> Default for AbilitiesPlugin<A> {
+    fn default() -> Self {
+        Self::new(AbilityStages::default())
+    }
+}
+
+impl<A: Actionlike, S: StageLabel + Clone> AbilitiesPlugin<A, S> {
+    pub fn new(stages: AbilityStages<S>) -> Self {
+        Self {
+            stages,
+            timestep: None,
+            _phantom: PhantomData::default(),
+        }
+    }
+
+    /// Advances cooldowns, casts, charges and regeneration by exactly `step` each time they run,
+    /// for deterministic simulations such as lockstep multiplayer
+    ///
+    /// The `main` stage of `stages` should run on a matching fixed timestep,
+    /// for example by adding it with the `FixedTimestep::step` run criteria.
+    pub fn fixed_timestep(mut self, step: Duration) -> Self {
+        self.timestep = Some(step);
+        self
+    }
+}
+
+/// The constant amount of time that ability systems advance by each time they run
+///
+/// Inserted by `AbilitiesPlugin::fixed_timestep`.
+/// When this resource is absent, systems advance by the frame delta of `Time`.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy::utils::Instant;
+/// use core::time::Duration;
+/// use leafwing_abilities::abilities::cooldowns::{tick_cooldowns, Cooldown};
+/// use leafwing_abilities::abilities::AbilityTimestep;
+///
+/// let mut world = World::new();
+/// let start = Instant::now();
+/// let mut time = Time::default();
+/// time.update_with_instant(start);
+/// world.insert_resource(time);
+/// world.insert_resource(AbilityTimestep(Duration::from_millis(100)));
+///
+/// let mut cooldown = Cooldown::new(2.0);
+/// cooldown.start();
+/// let fireball = world.spawn().insert(cooldown).id();
+///
+/// // Uneven frame times do not change the result of each step
+/// let mut stage = SystemStage::single(tick_cooldowns);
+/// let mut now = start;
+/// for step in 0..15 {
+///     now += Duration::from_millis(7 + 13 * step);
+///     world.get_resource_mut::<Time>().unwrap().update_with_instant(now);
+///     stage.run(&mut world);
+/// }
+///
+/// let remaining = world.get::<Cooldown>(fireball).unwrap().remaining_seconds();
+/// assert!((remaining - 0.5).abs() < 1e-4);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AbilityTimestep(pub Duration);
+
+/// The amount of time that ability systems should advance by when run
+pub fn ability_delta(time: &Time, timestep: Option<Res<AbilityTimestep>>) -> Duration {
+    match timestep {
+        Some(timestep) => timestep.0,
+        None => time.delta(),
+    }
+}
+
+/// Scales the time that passes for the cooldowns and resource regeneration of a single entity,
+/// such as for units caught in a slow field
+///
+/// Insert this on ability entities to scale their `Cooldown`, and on units to scale the regeneration of their `ResourcePool`s.
+/// Entities without this component use the full delta.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy::utils::Instant;
+/// use core::time::Duration;
+/// use leafwing_abilities::abilities::cooldowns::{tick_cooldowns, Cooldown};
+/// use leafwing_abilities::abilities::LocalTimeScale;
+///
+/// let mut world = World::new();
+/// let start = Instant::now();
+/// let mut time = Time::default();
+/// time.update_with_instant(start);
+/// world.insert_resource(time);
+///
+/// let mut cooldown = Cooldown::new(4.0);
+/// cooldown.start();
+/// let normal = world.spawn().insert(cooldown.clone()).id();
+/// let slowed = world.spawn().insert(cooldown).insert(LocalTimeScale(0.5)).id();
+///
+/// let mut time = world.get_resource_mut::<Time>().unwrap();
+/// time.update_with_instant(start + Duration::from_secs(2));
+/// SystemStage::single(tick_cooldowns).run(&mut world);
+///
+/// assert_eq!(world.get::<Cooldown>(normal).unwrap().remaining_seconds(), 2.0);
+/// assert_eq!(world.get::<Cooldown>(slowed).unwrap().remaining_seconds(), 3.0);
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct LocalTimeScale(pub f32);
+
+impl Default for LocalTimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl LocalTimeScale {
+    /// Scales `delta` by the time scale of an entity, if it has one
+    ///
+    /// Negative scales are treated as zero.
+    pub fn scale(local_time_scale: Option<&Self>, delta: Duration) -> Duration {
+        match local_time_scale {
+            Some(local_time_scale) => delta.mul_f32(local_time_scale.0.max(0.0)),
+            None => delta,
+        }
+    }
+}
+
+/// The stages that the systems of this crate are added to
+///
+/// Pass the same stages to `ResourcePoolExt::add_resource_pool_in_stages`
+/// and `ResourcePoolExt::add_resource_threshold_in_stages`
+/// so that resources are checked and spent alongside the abilities that use them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AbilityStages<S: StageLabel + Clone> {
+    /// Abilities are maintained, checked and chosen here, in the order given by `AbilityLabel`
+    pub main: S,
+    /// Effects which must wait until abilities have been used, like overclocked cooldown reductions
+    pub post: S,
+}
+
+impl Default for AbilityStages<CoreStage> {
+    fn default() -> Self {
+        Self {
+            main: CoreStage::PreUpdate,
+            post: CoreStage::PostUpdate,
+        }
+    }
+}
 
-impl Plugin for AbilitiesPlugin {
+impl<A: Actionlike, S: StageLabel + Clone> Plugin for AbilitiesPlugin<A, S> {
     fn build(&self, app: &mut App) {
+        let stages = &self.stages;
+
+        if let Some(step) = self.timestep {
+            app.insert_resource(AbilityTimestep(step));
+        }
+
+        app.add_event::<cooldowns::CooldownFinished>()
+            .add_event::<cooldowns::ReduceCooldown>()
+            .add_event::<cooldowns::StartCooldown>()
+            .add_event::<cooldowns::CooldownStarted>()
+            .add_event::<AbilityCancelled>()
+            .add_event::<AbilityActivated>()
+            .add_event::<Interrupt>();
+
+        app.register_type::<cooldowns::Cooldown>();
+        // Resource pools may have already registered their gates
+        app.init_resource::<usability::GateChecks>();
+
         app.add_system_to_stage(
-            CoreStage::PreUpdate,
+            stages.main.clone(),
+            systems::handle_interrupts.before(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
             systems::set_all_abilities_usable
                 .label(AbilityLabel::Maintain)
                 .before(AbilityLabel::Check),
         )
         .add_system_to_stage(
-            CoreStage::PreUpdate,
+            stages.main.clone(),
+            systems::prune_despawned_abilities
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            systems::advance_ability_state
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            disabled::tick_disabled
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            diminishing_returns::tick_diminishing_returns
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            empowerment::decay_empowerment
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            ability_mapping::tick_ability_maps::<A>
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
             cooldowns::tick_cooldowns
                 .label(AbilityLabel::Maintain)
                 .before(AbilityLabel::Check),
         )
         .add_system_to_stage(
-            CoreStage::PreUpdate,
+            stages.main.clone(),
+            cooldowns::reduce_cooldowns
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            cooldowns::start_cooldowns
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
             cooldowns::check_cooldowns
                 .label(AbilityLabel::Check)
                 .after(AbilityLabel::Maintain),
         )
         .add_system_to_stage(
-            CoreStage::PreUpdate,
+            stages.main.clone(),
+            cooldowns::check_shared_charges
+                .label(AbilityLabel::Check)
+                .after(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            cooldowns::update_cooldown_displays.after(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            cooldowns::send_cooldown_finished_events
+                .label(AbilityLabel::Check)
+                .after(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
             disabled::check_for_disabled_abilities
                 .label(AbilityLabel::Check)
                 .after(AbilityLabel::Maintain),
         )
         .add_system_to_stage(
-            CoreStage::PreUpdate,
+            stages.main.clone(),
+            passive::check_for_passive_abilities
+                .label(AbilityLabel::Check)
+                .after(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            range::check_range
+                .label(AbilityLabel::Check)
+                .after(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            movement::check_movement
+                .label(AbilityLabel::Check)
+                .after(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            prerequisites::check_required_abilities
+                .label(AbilityLabel::Check)
+                .after(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            ammo::check_ammo
+                .label(AbilityLabel::Check)
+                .after(AbilityLabel::Maintain),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            cooldowns::tick_global_cooldowns
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
             usability::update_ability_usability
+                .label(AbilityLabel::Usability)
                 .after(AbilityLabel::Check)
                 .before(AbilityLabel::Decide),
         )
         .add_system_to_stage(
-            CoreStage::PreUpdate,
-            ability_mapping::choose_ability_from_input
+            stages.main.clone(),
+            cooldowns::check_global_cooldowns
+                .after(AbilityLabel::Usability)
+                .before(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            crate::resources::check_alternate_costs
+                .after(AbilityLabel::Usability)
+                .before(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            crate::resources::reset_alternate_costs
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            ability_mapping::choose_ability_from_input::<A>
                 .label(AbilityLabel::Decide)
-                .after(InputLabel::Processing)
+                .after(InputManagerSystem::Update)
                 .after(AbilityLabel::Check),
         )
-        .add_system_to_stage(CoreStage::Last, systems::active_ability_cleanup);
+        .add_system_to_stage(
+            stages.main.clone(),
+            systems::send_ability_activated_events
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            cooldowns::start_global_cooldowns
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            cooldowns::start_linked_cooldowns
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            cooldowns::spend_shared_charges
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            ammo::spend_ammo
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            empowerment::apply_empowerment
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            cooldowns::trigger_cooldowns
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            diminishing_returns::record_diminishing_returns.after(AbilityLabel::Effect),
+        )
+        .add_system_to_stage(stages.post.clone(), cooldowns::apply_overclock_reductions);
+
+        #[cfg(feature = "telemetry")]
+        app.add_system_to_stage(
+            stages.main.clone(),
+            telemetry::record_activations
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        );
     }
 }
 
 #[derive(SystemLabel, Clone, Copy, Hash, PartialEq, Eq, Debug)]
 pub enum AbilityLabel {
-    /// Runs in CoreStage::PreUpdate
+    /// Runs in `AbilityStages::main`
     Maintain,
-    /// Runs in CoreStage::PreUpdate
+    /// Runs in `AbilityStages::main`
     Check,
-    /// Runs in CoreStage::PreUpdate
+    /// Runs in `AbilityStages::main`
+    Usability,
+    /// Runs in `AbilityStages::main`
     Decide,
+    /// Runs in `AbilityStages::main`, after `Decide`, while newly used abilities are `AbilityState::JustStarted`
+    ///
+    /// Costs are paid and cooldowns started here.
+    /// Add the systems that apply the effects of your abilities with
+    /// `.label(AbilityLabel::Effect).after(AbilityLabel::Decide)`,
+    /// or `.after(AbilityLabel::Effect)` if they need costs to have been paid first.
+    /// Every `JustStarted` ability is seen by these systems exactly once,
+    /// before it becomes `Active` at the next `Maintain`.
+    Effect,
 }
 
 /// Marker component for Ability entities
 #[derive(Component, Clone, Copy)]
 pub struct Ability;
 
+/// Marker component for abilities which are switched on and off by their input, like stances and auras
+///
+/// Toggled abilities are tracked separately from the `active_ability` of their unit,
+/// so they can stay on while the unit uses its other abilities.
+/// Attach a `Channeled` component to drain resources while the ability is on.
+#[derive(Component, Clone, Copy)]
+pub struct Toggleable;
+
+/// The slot an ability occupies while it is active
+///
+/// Each unit can have one active ability per category at once,
+/// so that e.g. a movement ability can be used while attacking.
+/// Abilities without this component belong to `AbilityCategory::DEFAULT`.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AbilityCategory(pub &'static str);
+
+impl AbilityCategory {
+    /// The category of abilities stored in `Abilities::active_ability`
+    pub const DEFAULT: Self = Self("default");
+}
+
+impl Default for AbilityCategory {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Freeform labels used to group abilities, such as "magic" or "movement"
+///
+/// Tags are stored on the ability entity, and so are shared by every unit that can use it.
+/// To prevent a unit from using a group of abilities, such as when silenced,
+/// insert `Disabled` on each of its `Abilities::abilities_with_tag`.
+#[derive(Component, Clone, PartialEq, Eq, Debug, Default)]
+pub struct AbilityTags(pub HashSet<Cow<'static, str>>);
+
+impl AbilityTags {
+    pub fn new(tags: impl IntoIterator<Item = impl Into<Cow<'static, str>>>) -> Self {
+        Self(tags.into_iter().map(Into::into).collect())
+    }
+
+    pub fn has(&self, tag: &str) -> bool {
+        self.0.contains(tag)
+    }
+}
+
 /// Component that stores the abilities that can be used by the unit
-#[derive(Component)]
+///
+/// Input maps are stored separately, in `AbilityMap`, so this can be cloned to spawn many identical units.
+#[derive(Component, Clone)]
 pub struct Abilities {
     ability_list: Vec<Entity>,
-    usable: HashMap<Entity, bool>,
+    usable: HashMap<Entity, UsabilityReason>,
+    /// The active ability of `AbilityCategory::DEFAULT`
     pub active_ability: ActiveAbility,
-    input_map: Box<dyn AbilityInputMap>,
+    /// The active abilities of every other `AbilityCategory`
+    concurrent: HashMap<AbilityCategory, ActiveAbility>,
+    toggled_on: HashSet<Entity>,
+    input_buffer: Option<InputBuffer>,
 }
 
 impl Abilities {
     pub fn from_ability_list(ability_list: Vec<Entity>) -> Self {
         let mut usable = HashMap::default();
         for &entity in ability_list.iter() {
-            usable.insert(entity, false);
+            usable.insert(entity, UsabilityReason::Unchecked);
         }
 
         Self {
             ability_list,
             usable,
             active_ability: ActiveAbility::NONE,
-            input_map: Box::new(NullAbilityMap),
+            concurrent: HashMap::default(),
+            toggled_on: HashSet::default(),
+            input_buffer: None,
+        }
+    }
+
+    /// Copies the current state of these abilities, such as for rollback networking
+    pub fn snapshot(&self) -> AbilitiesSnapshot {
+        AbilitiesSnapshot {
+            ability_list: self.ability_list.clone(),
+            usable: self.usable.clone(),
+            active_ability: self.active_ability,
+            concurrent: self.concurrent.clone(),
+            toggled_on: self.toggled_on.clone(),
+            input_buffer: self.input_buffer.clone(),
         }
     }
 
-    pub fn from_ability_map(map: impl AbilityInputMap) -> Self {
-        let ability_list = map.ability_list();
+    /// Restores the state previously recorded by `Abilities::snapshot`
+    pub fn restore_from(&mut self, snapshot: AbilitiesSnapshot) {
+        *self = Self {
+            ability_list: snapshot.ability_list,
+            usable: snapshot.usable,
+            active_ability: snapshot.active_ability,
+            concurrent: snapshot.concurrent,
+            toggled_on: snapshot.toggled_on,
+            input_buffer: snapshot.input_buffer,
+        };
+    }
 
-        let mut usable = HashMap::default();
-        for &entity in ability_list.iter() {
-            usable.insert(entity, false);
+    /// Grants a new ability to this unit, such as when a spell is learned
+    ///
+    /// Like all abilities, it is not usable until its usability has been checked.
+    /// Adding an ability that the unit already has does nothing.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::usability::UsabilityReason;
+    /// use leafwing_abilities::abilities::{Abilities, AbilityState, ActiveAbility};
+    ///
+    /// let fireball = Entity::from_raw(0);
+    /// let mut abilities = Abilities::default();
+    ///
+    /// abilities.add_ability(fireball);
+    /// assert_eq!(abilities.ability_list(), vec![fireball]);
+    /// assert_eq!(abilities.is_usable(fireball), Some(false));
+    /// assert_eq!(abilities.usability(fireball), Some(UsabilityReason::Unchecked));
+    ///
+    /// abilities.active_ability = ActiveAbility::new(fireball, AbilityState::Active);
+    /// abilities.remove_ability(fireball);
+    /// assert!(abilities.ability_list().is_empty());
+    /// assert_eq!(abilities.is_usable(fireball), None);
+    /// assert!(abilities.active_ability() == ActiveAbility::NONE);
+    /// ```
+    pub fn add_ability(&mut self, ability_entity: Entity) {
+        if self.usable.contains_key(&ability_entity) {
+            return;
         }
 
-        Self {
-            ability_list,
-            usable,
-            active_ability: ActiveAbility::NONE,
-            input_map: Box::new(map),
+        self.ability_list.push(ability_entity);
+        self.usable
+            .insert(ability_entity, UsabilityReason::Unchecked);
+    }
+
+    /// Removes an ability from this unit, ending it if it was active, toggled on or buffered
+    pub fn remove_ability(&mut self, ability_entity: Entity) {
+        self.ability_list.retain(|&entity| entity != ability_entity);
+        self.usable.remove(&ability_entity);
+        self.toggled_on.remove(&ability_entity);
+
+        for active_ability in self.active_abilities_mut() {
+            if active_ability.entity == Some(ability_entity) {
+                *active_ability = ActiveAbility::NONE;
+            }
+        }
+
+        if self.buffered_input() == Some(ability_entity) {
+            self.clear_input_buffer();
         }
     }
 
+    /// Uses every ability that can be chosen by the provided map
+    pub fn from_ability_map<A: Actionlike>(map: &AbilityMap<A>) -> Self {
+        Self::from_ability_list(map.ability_list())
+    }
+
+    /// Remembers abilities chosen while another ability is active for up to `buffer_duration`,
+    /// starting them as soon as the unit is free to do so
+    pub fn with_input_buffer(mut self, buffer_duration: Duration) -> Self {
+        self.input_buffer = Some(InputBuffer {
+            duration: buffer_duration,
+            buffered: None,
+            age: Duration::ZERO,
+        });
+        self
+    }
+
     pub fn active_ability(&self) -> ActiveAbility {
         self.active_ability
     }
 
-    pub fn ability_list(&self) -> Vec<Entity> {
-        self.ability_list.clone()
+    /// Whether the provided ability can currently be used,
+    /// or `None` if it is not one of this unit's abilities
+    pub fn is_usable(&self, ability_entity: Entity) -> Option<bool> {
+        self.usability(ability_entity)
+            .map(|usability| usability == UsabilityReason::Ready)
     }
 
-    pub(crate) fn process_input(&self, action_state: &ActionState) -> Option<Entity> {
-        self.input_map
-            .process_input(action_state, self.usable.clone())
+    /// Why the provided ability can or cannot currently be used,
+    /// or `None` if it is not one of this unit's abilities
+    pub fn usability(&self, ability_entity: Entity) -> Option<UsabilityReason> {
+        self.usable.get(&ability_entity).copied()
     }
 
-    pub(crate) fn set_usable(&mut self, ability_entity: Entity, usable: Usable) {
-        self.usable.insert(ability_entity, usable.0);
+    /// The abilities of this unit which can currently be used, in the order of `ability_list`
+    pub fn usable_abilities(&self) -> Vec<Entity> {
+        self.ability_list
+            .iter()
+            .copied()
+            .filter(|&ability_entity| self.is_usable(ability_entity) == Some(true))
+            .collect()
     }
-}
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-pub struct ActiveAbility {
-    pub entity: Option<Entity>,
-    pub state: AbilityState,
-}
+    /// The abilities of this unit which cannot currently be used, in the order of `ability_list`
+    pub fn unusable_abilities(&self) -> Vec<Entity> {
+        self.ability_list
+            .iter()
+            .copied()
+            .filter(|&ability_entity| self.is_usable(ability_entity) != Some(true))
+            .collect()
+    }
 
-impl ActiveAbility {
-    const NONE: Self = Self {
-        entity: None,
-        state: AbilityState::Idle,
-    };
-}
+    /// The abilities of this unit whose `AbilityTags` include `tag`
+    pub fn abilities_with_tag(&self, tag: &str, tag_query: &Query<&AbilityTags>) -> Vec<Entity> {
+        self.ability_list
+            .iter()
+            .copied()
+            .filter(|&ability_entity| {
+                tag_query
+                    .get(ability_entity)
+                    .map_or(false, |tags| tags.has(tag))
+            })
+            .collect()
+    }
 
-impl Default for Abilities {
-    fn default() -> Self {
-        Self {
-            ability_list: Vec::default(),
-            usable: HashMap::default(),
-            active_ability: ActiveAbility::NONE,
-            input_map: Box::new(NullAbilityMap),
+    /// The active ability of the provided category
+    pub fn slot(&self, category: AbilityCategory) -> ActiveAbility {
+        if category == AbilityCategory::DEFAULT {
+            self.active_ability
+        } else {
+            self.concurrent
+                .get(&category)
+                .copied()
+                .unwrap_or(ActiveAbility::NONE)
         }
     }
-}
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub enum AbilityState {
-    JustStarted,
-    Active,
-    Idle,
-}
+    pub fn slot_mut(&mut self, category: AbilityCategory) -> &mut ActiveAbility {
+        if category == AbilityCategory::DEFAULT {
+            &mut self.active_ability
+        } else {
+            self.concurrent
+                .entry(category)
+                .or_insert(ActiveAbility::NONE)
+        }
+    }
 
-pub mod usability {
-    use bevy::prelude::*;
+    /// The active abilities of every category, including those which are idle
+    pub fn active_abilities(&self) -> impl Iterator<Item = &ActiveAbility> + '_ {
+        core::iter::once(&self.active_ability).chain(self.concurrent.values())
+    }
 
-    use super::{Abilities, Ability};
+    pub fn active_abilities_mut(&mut self) -> impl Iterator<Item = &mut ActiveAbility> + '_ {
+        core::iter::once(&mut self.active_ability).chain(self.concurrent.values_mut())
+    }
 
-    #[derive(Component, Clone, Copy)]
-    pub struct Usable(pub(crate) bool);
+    /// Stops the active ability, returning its entity if there was one
+    ///
+    /// Abilities that are interrupted while `Casting` never take effect.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::{Abilities, AbilityState, ActiveAbility};
+    ///
+    /// let fireball = Entity::from_raw(0);
+    /// let mut abilities = Abilities::from_ability_list(vec![fireball]);
+    /// abilities.active_ability = ActiveAbility::new(fireball, AbilityState::Casting);
+    ///
+    /// assert_eq!(abilities.interrupt(), Some(fireball));
+    /// assert!(abilities.active_ability == ActiveAbility::NONE);
+    /// ```
+    pub fn interrupt(&mut self) -> Option<Entity> {
+        let interrupted = self.active_ability.entity;
+        self.active_ability = ActiveAbility::NONE;
+        interrupted
+    }
 
-    pub(crate) fn update_ability_usability(
-        mut unit_query: Query<&mut Abilities>,
-        ability_query: Query<&Usable, With<Ability>>,
-    ) {
-        for mut unit_abilties in unit_query.iter_mut() {
-            for ability_entity in unit_abilties.ability_list() {
-                let usable = *ability_query.get(ability_entity).unwrap();
-                unit_abilties.set_usable(ability_entity, usable);
+    /// Stops every ability that is still `Casting`, in any category, returning their entities
+    ///
+    /// Abilities that have already taken effect are unaffected.
+    pub fn cancel_casts(&mut self) -> Vec<Entity> {
+        let mut cancelled = Vec::new();
+        for active_ability in self.active_abilities_mut() {
+            if active_ability.state == AbilityState::Casting {
+                cancelled.extend(active_ability.entity);
+                *active_ability = ActiveAbility::NONE;
             }
         }
+        cancelled
     }
-}
 
-pub mod systems {
-    use super::*;
+    /// Immediately starts the provided ability, such as during scripted encounters
+    ///
+    /// The ability replaces the active ability of `AbilityCategory::DEFAULT`, and takes effect immediately,
+    /// skipping any cast time and usability checks.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::{Abilities, AbilityState, UnknownAbility};
+    ///
+    /// let fireball = Entity::from_raw(0);
+    /// let mut abilities = Abilities::from_ability_list(vec![fireball]);
+    ///
+    /// assert_eq!(abilities.activate(fireball), Ok(()));
+    /// assert_eq!(abilities.active_ability().state, AbilityState::JustStarted);
+    ///
+    /// let unknown = Entity::from_raw(1);
+    /// assert_eq!(abilities.activate(unknown), Err(UnknownAbility(unknown)));
+    /// ```
+    pub fn activate(&mut self, ability_entity: Entity) -> Result<(), UnknownAbility> {
+        if !self.ability_list.contains(&ability_entity) {
+            return Err(UnknownAbility(ability_entity));
+        }
 
-    /// Abilities start life each frame as `Usable`, and then are disabled by various systems
-    pub fn set_all_abilities_usable(mut query: Query<&mut Usable>) {
-        for mut usable in query.iter_mut() {
-            *usable = Usable(true);
+        self.active_ability = ActiveAbility::new(ability_entity, AbilityState::JustStarted);
+        Ok(())
+    }
+
+    /// Ends the active ability of every category, without sending any events
+    pub fn deactivate(&mut self) {
+        for active_ability in self.active_abilities_mut() {
+            *active_ability = ActiveAbility::NONE;
         }
     }
 
-    /// Abilities are no longer `JustStarted` after one frame
-    pub fn active_ability_cleanup(mut query: Query<&mut Abilities>) {
+    pub fn ability_list(&self) -> Vec<Entity> {
+        self.ability_list.clone()
+    }
+
+    /// Switches a `Toggleable` ability on or off, returning `true` if it is now on
+    pub fn toggle(&mut self, ability_entity: Entity) -> bool {
+        if self.toggled_on.remove(&ability_entity) {
+            false
+        } else {
+            self.toggled_on.insert(ability_entity);
+            true
+        }
+    }
+
+    pub fn is_toggled_on(&self, ability_entity: Entity) -> bool {
+        self.toggled_on.contains(&ability_entity)
+    }
+
+    /// The `Toggleable` abilities that are currently switched on
+    pub fn toggled_on(&self) -> Vec<Entity> {
+        self.toggled_on.iter().cloned().collect()
+    }
+
+    pub(crate) fn set_usable(&mut self, ability_entity: Entity, usable: Usable) {
+        self.usable.insert(ability_entity, usable.0);
+    }
+
+    /// Marks the provided ability as unusable, unless it is already unusable for a more important reason
+    pub(crate) fn block_usable(&mut self, ability_entity: Entity, reason: UsabilityReason) {
+        if let Some(usability) = self.usable.get_mut(&ability_entity) {
+            *usability = (*usability).max(reason);
+        }
+    }
+
+    /// Whether each ability can currently be used, as consumed by `AbilityInputMap`s
+    pub(crate) fn usable_map(&self) -> HashMap<Entity, bool> {
+        self.usable
+            .iter()
+            .map(|(&ability_entity, &usability)| {
+                (ability_entity, usability == UsabilityReason::Ready)
+            })
+            .collect()
+    }
+
+    /// Stores the chosen ability in the input buffer, if this unit has one
+    pub(crate) fn buffer_input(&mut self, ability_entity: Entity) {
+        if let Some(input_buffer) = &mut self.input_buffer {
+            input_buffer.buffered = Some(ability_entity);
+            input_buffer.age = Duration::ZERO;
+        }
+    }
+
+    /// The buffered ability, if any
+    pub(crate) fn buffered_input(&self) -> Option<Entity> {
+        self.input_buffer.as_ref()?.buffered
+    }
+
+    pub(crate) fn clear_input_buffer(&mut self) {
+        if let Some(input_buffer) = &mut self.input_buffer {
+            input_buffer.buffered = None;
+        }
+    }
+
+    /// Removes and returns the buffered ability, if it can currently be used
+    pub(crate) fn take_buffered_input(&mut self) -> Option<Entity> {
+        let input_buffer = self.input_buffer.as_mut()?;
+        let ability_entity = input_buffer.buffered?;
+
+        // Buffered abilities that cannot be used yet are kept until they expire
+        if *self.usable.get(&ability_entity)? == UsabilityReason::Ready {
+            input_buffer.buffered = None;
+            Some(ability_entity)
+        } else {
+            None
+        }
+    }
+
+    /// Ages the buffered ability, discarding it once it has been stored for too long
+    pub(crate) fn tick_input_buffer(&mut self, delta: Duration) {
+        if let Some(input_buffer) = &mut self.input_buffer {
+            if input_buffer.buffered.is_some() {
+                input_buffer.age += delta;
+                if input_buffer.age > input_buffer.duration {
+                    input_buffer.buffered = None;
+                }
+            }
+        }
+    }
+}
+
+/// The provided entity is not one of the unit's abilities
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnknownAbility(pub Entity);
+
+impl core::fmt::Display for UnknownAbility {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?} is not in the unit's ability list", self.0)
+    }
+}
+
+impl std::error::Error for UnknownAbility {}
+
+/// A copy of the state of a unit's `Abilities`, used to roll them back
+///
+/// Input maps are stored separately, in `AbilityMap`, and are not included.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AbilitiesSnapshot {
+    pub ability_list: Vec<Entity>,
+    pub usable: HashMap<Entity, UsabilityReason>,
+    pub active_ability: ActiveAbility,
+    pub concurrent: HashMap<AbilityCategory, ActiveAbility>,
+    pub toggled_on: HashSet<Entity>,
+    input_buffer: Option<InputBuffer>,
+}
+
+/// Remembers the last ability chosen while another ability was active
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct InputBuffer {
+    duration: Duration,
+    buffered: Option<Entity>,
+    age: Duration,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ActiveAbility {
+    pub entity: Option<Entity>,
+    pub state: AbilityState,
+    /// What the ability was aimed at when it was chosen
+    ///
+    /// Cleared along with the rest of the slot when it is reset to `ActiveAbility::NONE`.
+    pub target: AbilityTarget,
+    /// How charged the ability was when it was chosen, from 0.0 to 1.0
+    ///
+    /// Abilities which cannot be charged are always fully charged.
+    pub charge: f32,
+    /// The time spent in the current state
+    elapsed: Duration,
+}
+
+impl ActiveAbility {
+    pub const NONE: Self = Self {
+        entity: None,
+        state: AbilityState::Idle,
+        target: AbilityTarget::None,
+        charge: 0.0,
+        elapsed: Duration::ZERO,
+    };
+
+    pub fn new(entity: Entity, state: AbilityState) -> Self {
+        Self {
+            entity: Some(entity),
+            state,
+            target: AbilityTarget::None,
+            charge: 1.0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// The time spent in the current state
+    ///
+    /// Resets to zero when the ability takes effect, then counts up while it remains active.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Records how charged the ability was, clamped between 0.0 and 1.0
+    pub fn with_charge(mut self, charge: f32) -> Self {
+        self.charge = charge.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Aims the ability at the provided target
+    ///
+    /// Units without `InputControlled` (such as AI) should use this when setting their active ability.
+    pub fn with_target(mut self, target: AbilityTarget) -> Self {
+        self.target = target;
+        self
+    }
+}
+
+/// The entity, location or direction that an ability is aimed at
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum AbilityTarget {
+    None,
+    Entity(Entity),
+    Point(Vec3),
+    Direction(Vec3),
+}
+
+impl Default for AbilityTarget {
+    fn default() -> Self {
+        AbilityTarget::None
+    }
+}
+
+/// The target that a unit's next ability will be aimed at
+///
+/// Stored on the unit entity, alongside its `Abilities`.
+/// Update this from cursor position, lock-on or AI logic as appropriate:
+/// input-controlled units copy it into `ActiveAbility::target` when an ability is chosen.
+#[derive(Component, Clone, Copy, PartialEq, Debug, Default)]
+pub struct CurrentTarget(pub AbilityTarget);
+
+impl Default for Abilities {
+    fn default() -> Self {
+        Self::from_ability_list(Vec::default())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum AbilityState {
+    /// The ability is winding up, and has not taken effect yet
+    Casting,
+    JustStarted,
+    Active,
+    Idle,
+}
+
+/// Send this event to interrupt the active ability of the `caster`, such as when they are stunned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Interrupt {
+    pub caster: Entity,
+}
+
+/// Sent when an ability is stopped before it could complete
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AbilityCancelled {
+    pub caster: Entity,
+    pub ability: Entity,
+    /// The state the ability was in when it was cancelled
+    pub state: AbilityState,
+}
+
+/// Sent on the frame that an ability takes effect, as it reaches `AbilityState::JustStarted`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AbilityActivated {
+    pub caster: Entity,
+    pub ability: Entity,
+}
+
+pub mod usability {
+    use bevy::prelude::*;
+
+    use super::{ammo, cooldowns, disabled, movement, passive, prerequisites, range};
+    use super::{Abilities, Ability};
+
+    /// Why an ability can or cannot currently be used, such as for the tooltip of a greyed-out button
+    ///
+    /// When an ability is unusable for several reasons, the one declared last here is reported.
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+    pub enum UsabilityReason {
+        Ready,
+        OutOfRange,
+        PrerequisiteInactive,
+        /// The caster is moving when the ability requires standing still, or vice versa
+        WrongMovementState,
+        InsufficientResource,
+        NoAmmo,
+        OnCooldown,
+        OnGlobalCooldown,
+        Disabled,
+        Passive,
+        /// The ability has not been checked yet, or has no `Usable` component
+        Unchecked,
+    }
+
+    /// Whether this ability can currently be used, and if not, why
+    ///
+    /// Reset to `UsabilityReason::Ready` at the start of each frame, then blocked by each check that fails.
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct Usable(pub(crate) UsabilityReason);
+
+    impl Usable {
+        pub fn is_usable(&self) -> bool {
+            self.0 == UsabilityReason::Ready
+        }
+
+        pub fn reason(&self) -> UsabilityReason {
+            self.0
+        }
+
+        /// Marks this ability as unusable, unless it is already unusable for a more important reason
+        pub(crate) fn block(&mut self, reason: UsabilityReason) {
+            if reason > self.0 {
+                self.0 = reason;
+            }
+        }
+    }
+
+    /// Checks one gate of an ability on demand, given the `World`, the caster and the ability,
+    /// returning the reason it cannot be used, if any
+    pub type GateCheck = fn(&World, Entity, Entity) -> Option<UsabilityReason>;
+
+    /// The gates evaluated by `usability_now`, mirroring the systems that run in `AbilityLabel::Check`
+    ///
+    /// The default registry contains every gate of this crate that does not depend on a resource type.
+    /// `ResourcePoolExt::add_resource_pool` registers the gate for each resource pool,
+    /// and your own gating systems should `add` a matching check here.
+    #[derive(Clone)]
+    pub struct GateChecks(Vec<GateCheck>);
+
+    impl Default for GateChecks {
+        fn default() -> Self {
+            Self(vec![
+                cooldowns::cooldown_gate,
+                cooldowns::global_cooldown_gate,
+                disabled::disabled_gate,
+                passive::passive_gate,
+                range::range_gate,
+                prerequisites::required_ability_gate,
+                ammo::ammo_gate,
+                movement::movement_gate,
+                crate::resources::alternate_cost_gate,
+            ])
+        }
+    }
+
+    impl GateChecks {
+        pub fn add(&mut self, gate: GateCheck) {
+            self.0.push(gate);
+        }
+    }
+
+    /// Why the `ability` of the `caster` can or cannot be used right now,
+    /// evaluating every registered `GateChecks` immediately rather than waiting for the next `AbilityLabel::Check`
+    ///
+    /// Abilities which the caster does not have, or which have no `Usable` component, are `UsabilityReason::Unchecked`.
+    /// If no `GateChecks` resource exists, the default gates are used.
+    ///
+    /// ```rust
+    /// use bevy::ecs::system::CommandQueue;
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    /// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+    /// use leafwing_abilities::abilities::usability::{can_use, usability_now, GateChecks, UsabilityReason};
+    /// use leafwing_abilities::abilities::Abilities;
+    /// use leafwing_abilities::resources::doc_resources::Mana;
+    /// use leafwing_abilities::resources::{resource_gate, ResourcePool};
+    ///
+    /// let mut world = World::new();
+    /// let mut gate_checks = GateChecks::default();
+    /// gate_checks.add(resource_gate::<Mana>);
+    /// world.insert_resource(gate_checks);
+    ///
+    /// let mut command_queue = CommandQueue::default();
+    /// let mut commands = Commands::new(&mut command_queue, &world);
+    /// let fireball = commands
+    ///     .spawn_ability()
+    ///     .with_cooldown(Cooldown::new(4.0))
+    ///     .with_cost(Mana(30))
+    ///     .id();
+    /// command_queue.apply(&mut world);
+    ///
+    /// let caster = world
+    ///     .spawn()
+    ///     .insert(Abilities::from_ability_list(vec![fireball]))
+    ///     .insert(ResourcePool::new(Mana(50), Mana(100), Mana(0)))
+    ///     .id();
+    ///
+    /// // No systems need to run first
+    /// assert!(can_use(&world, caster, fireball));
+    ///
+    /// world.get_mut::<ResourcePool<Mana>>(caster).unwrap().set_current(Mana(10));
+    /// assert_eq!(usability_now(&world, caster, fireball), UsabilityReason::InsufficientResource);
+    ///
+    /// world.get_mut::<Cooldown>(fireball).unwrap().start();
+    /// assert_eq!(usability_now(&world, caster, fireball), UsabilityReason::OnCooldown);
+    /// ```
+    pub fn usability_now(world: &World, caster: Entity, ability: Entity) -> UsabilityReason {
+        let known = world
+            .get::<Abilities>(caster)
+            .map_or(false, |abilities| abilities.usable.contains_key(&ability));
+        if !known || world.get::<Usable>(ability).is_none() {
+            return UsabilityReason::Unchecked;
+        }
+
+        let default_gate_checks;
+        let gate_checks = match world.get_resource::<GateChecks>() {
+            Some(gate_checks) => gate_checks,
+            None => {
+                default_gate_checks = GateChecks::default();
+                &default_gate_checks
+            }
+        };
+
+        gate_checks
+            .0
+            .iter()
+            .filter_map(|gate| gate(world, caster, ability))
+            .max()
+            .unwrap_or(UsabilityReason::Ready)
+    }
+
+    /// Whether the `ability` of the `caster` can be used right now, without waiting a frame
+    ///
+    /// See `usability_now` for details.
+    pub fn can_use(world: &World, caster: Entity, ability: Entity) -> bool {
+        usability_now(world, caster, ability) == UsabilityReason::Ready
+    }
+
+    pub(crate) fn update_ability_usability(
+        mut unit_query: Query<&mut Abilities>,
+        ability_query: Query<&Usable, With<Ability>>,
+    ) {
+        for mut unit_abilties in unit_query.iter_mut() {
+            for ability_entity in unit_abilties.ability_list() {
+                // Abilities without a `Usable` component (or which have been despawned) cannot be checked,
+                // and so are never usable
+                let usable = ability_query
+                    .get(ability_entity)
+                    .copied()
+                    .unwrap_or(Usable(UsabilityReason::Unchecked));
+                unit_abilties.set_usable(ability_entity, usable);
+            }
+        }
+    }
+}
+
+pub mod systems {
+    use super::casting::CastTime;
+    use super::*;
+    use bevy::ecs::entity::Entities;
+
+    /// Abilities start life each frame as `Usable`, and then are disabled by various systems
+    pub fn set_all_abilities_usable(mut query: Query<&mut Usable>) {
+        for mut usable in query.iter_mut() {
+            *usable = Usable(UsabilityReason::Ready);
+        }
+    }
+
+    /// Removes abilities which have been despawned, such as dropped weapons, from the `Abilities` of every unit
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::systems::prune_despawned_abilities;
+    /// use leafwing_abilities::abilities::{Abilities, Ability, AbilityState, ActiveAbility};
+    ///
+    /// let mut world = World::new();
+    /// let sword_slash = world.spawn().insert(Ability).id();
+    /// let kick = world.spawn().insert(Ability).id();
+    ///
+    /// let mut abilities = Abilities::from_ability_list(vec![sword_slash, kick]);
+    /// abilities.active_ability = ActiveAbility::new(sword_slash, AbilityState::Active);
+    /// let caster = world.spawn().insert(abilities).id();
+    ///
+    /// // The sword is dropped mid-swing
+    /// world.despawn(sword_slash);
+    /// SystemStage::single(prune_despawned_abilities).run(&mut world);
+    ///
+    /// let abilities = world.get::<Abilities>(caster).unwrap();
+    /// assert_eq!(abilities.ability_list(), vec![kick]);
+    /// assert!(abilities.active_ability() == ActiveAbility::NONE);
+    /// ```
+    pub fn prune_despawned_abilities(entities: &Entities, mut query: Query<&mut Abilities>) {
         for mut abilities in query.iter_mut() {
-            if abilities.active_ability.state == AbilityState::JustStarted {
-                abilities.active_ability.state = AbilityState::Active;
+            // Checking first avoids change-detection false positives
+            let despawned: Vec<Entity> = abilities
+                .ability_list
+                .iter()
+                .copied()
+                .filter(|&ability_entity| !entities.contains(ability_entity))
+                .collect();
+
+            for ability_entity in despawned {
+                debug!(
+                    "Removing the despawned ability {:?} from a unit's abilities",
+                    ability_entity
+                );
+                abilities.remove_ability(ability_entity);
+            }
+        }
+    }
+
+    /// Interrupts the active abilities of every category of units targeted by `Interrupt` events
+    ///
+    /// Runs before casts are advanced, so interrupted casts never reach `AbilityState::JustStarted`.
+    pub fn handle_interrupts(
+        mut interrupt_reader: EventReader<Interrupt>,
+        mut cancelled_writer: EventWriter<AbilityCancelled>,
+        mut query: Query<&mut Abilities>,
+    ) {
+        for &Interrupt { caster } in interrupt_reader.iter() {
+            if let Ok(mut abilities) = query.get_mut(caster) {
+                for active_ability in abilities.active_abilities_mut() {
+                    if let Some(ability) = active_ability.entity {
+                        cancelled_writer.send(AbilityCancelled {
+                            caster,
+                            ability,
+                            state: active_ability.state,
+                        });
+                    }
+                    *active_ability = ActiveAbility::NONE;
+                }
+            }
+        }
+    }
+
+    /// Sends an `AbilityActivated` event for each ability that just took effect
+    ///
+    /// ```rust
+    /// use bevy::app::{Events, ManualEventReader};
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::systems::send_ability_activated_events;
+    /// use leafwing_abilities::abilities::{Abilities, AbilityActivated, AbilityState, ActiveAbility};
+    ///
+    /// let mut world = World::new();
+    /// world.insert_resource(Events::<AbilityActivated>::default());
+    ///
+    /// let fireball = world.spawn().id();
+    /// let mut abilities = Abilities::from_ability_list(vec![fireball]);
+    /// abilities.active_ability = ActiveAbility::new(fireball, AbilityState::JustStarted);
+    /// let caster = world.spawn().insert(abilities).id();
+    ///
+    /// SystemStage::single(send_ability_activated_events).run(&mut world);
+    ///
+    /// let events = world.get_resource::<Events<AbilityActivated>>().unwrap();
+    /// let sent: Vec<_> = ManualEventReader::default().iter(events).copied().collect();
+    /// assert_eq!(sent, vec![AbilityActivated { caster, ability: fireball }]);
+    /// ```
+    pub fn send_ability_activated_events(
+        query: Query<(Entity, &Abilities)>,
+        mut event_writer: EventWriter<AbilityActivated>,
+    ) {
+        for (caster, abilities) in query.iter() {
+            for active_ability in abilities.active_abilities() {
+                if active_ability.state != AbilityState::JustStarted {
+                    continue;
+                }
+
+                if let Some(ability) = active_ability.entity {
+                    event_writer.send(AbilityActivated { caster, ability });
+                }
+            }
+        }
+    }
+
+    /// Moves each active ability to its next `AbilityState`, once per frame
+    ///
+    /// This is the only system which changes the state of an existing `ActiveAbility`:
+    /// new abilities are started in their `initial_state`, and cancelled abilities are replaced by `ActiveAbility::NONE`.
+    /// The transitions, and the guards which must hold for them to occur, are:
+    ///
+    /// - `Casting` to `JustStarted`, once the `CastTime` of the ability has elapsed
+    /// - `JustStarted` to `Active`, on the frame after the ability took effect
+    /// - `Active` and `Idle` are never left, except by replacing the ability
+    ///
+    /// The elapsed time of each ability is advanced here too,
+    /// resetting to zero as it takes effect.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use bevy::utils::Instant;
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::casting::CastTime;
+    /// use leafwing_abilities::abilities::systems::advance_ability_state;
+    /// use leafwing_abilities::abilities::{Abilities, Ability, AbilityState, ActiveAbility};
+    ///
+    /// let mut world = World::new();
+    /// let start = Instant::now();
+    /// let mut time = Time::default();
+    /// time.update_with_instant(start);
+    /// world.insert_resource(time);
+    ///
+    /// let pyroblast = world
+    ///     .spawn()
+    ///     .insert(Ability)
+    ///     .insert(CastTime(Duration::from_secs(2)))
+    ///     .id();
+    /// let mut abilities = Abilities::from_ability_list(vec![pyroblast]);
+    /// abilities.active_ability = ActiveAbility::new(pyroblast, AbilityState::Casting);
+    /// let caster = world.spawn().insert(abilities).id();
+    ///
+    /// let mut stage = SystemStage::single(advance_ability_state);
+    /// let mut states = Vec::new();
+    /// for frame in 1..=4 {
+    ///     let mut time = world.get_resource_mut::<Time>().unwrap();
+    ///     time.update_with_instant(start + Duration::from_secs(frame));
+    ///     stage.run(&mut world);
+    ///     states.push(world.get::<Abilities>(caster).unwrap().active_ability().state);
+    /// }
+    ///
+    /// assert_eq!(
+    ///     states,
+    ///     vec![
+    ///         AbilityState::Casting,
+    ///         AbilityState::JustStarted,
+    ///         AbilityState::Active,
+    ///         AbilityState::Active
+    ///     ]
+    /// );
+    /// ```
+    pub fn advance_ability_state(
+        mut unit_query: Query<&mut Abilities>,
+        cast_time_query: Query<&CastTime, With<Ability>>,
+        time: Res<Time>,
+        timestep: Option<Res<AbilityTimestep>>,
+    ) {
+        let delta = ability_delta(&time, timestep);
+        for mut abilities in unit_query.iter_mut() {
+            for active_ability in abilities.active_abilities_mut() {
+                match active_ability.state {
+                    AbilityState::Casting => {
+                        let cast_time = active_ability
+                            .entity
+                            .and_then(|ability_entity| cast_time_query.get(ability_entity).ok())
+                            .map(|cast_time| cast_time.0)
+                            .unwrap_or_default();
+
+                        active_ability.elapsed += delta;
+                        if active_ability.elapsed >= cast_time {
+                            active_ability.state = AbilityState::JustStarted;
+                            active_ability.elapsed = Duration::ZERO;
+                        }
+                    }
+                    AbilityState::JustStarted => {
+                        active_ability.state = AbilityState::Active;
+                        active_ability.elapsed += delta;
+                    }
+                    AbilityState::Active => {
+                        active_ability.elapsed += delta;
+                    }
+                    AbilityState::Idle => (),
+                }
+            }
+        }
+    }
+}
+
+pub mod casting {
+    use super::*;
+
+    /// The time an ability spends in `AbilityState::Casting` before it takes effect
+    ///
+    /// Abilities without this component take effect immediately.
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct CastTime(pub Duration);
+
+    /// The state that a newly chosen ability should begin in
+    pub fn initial_state(cast_time: Option<&CastTime>) -> AbilityState {
+        match cast_time {
+            Some(&CastTime(duration)) if duration > Duration::ZERO => AbilityState::Casting,
+            _ => AbilityState::JustStarted,
+        }
+    }
+}
+
+pub mod disabled {
+    use super::*;
+
+    /// Marker component for abilities which cannot be used for miscallaneous reasons
+    ///
+    /// Temporary disables, such as silences, are removed automatically once they expire.
+    #[derive(Component, Clone)]
+    pub struct Disabled {
+        timer: Option<Timer>,
+    }
+
+    impl Disabled {
+        /// Disables the ability until this component is removed
+        pub fn permanent() -> Self {
+            Self { timer: None }
+        }
+
+        /// Disables the ability for the provided `duration`
+        pub fn for_duration(duration: Duration) -> Self {
+            Self {
+                timer: Some(Timer::new(duration, false)),
+            }
+        }
+
+        /// The time until this disable expires, or `None` if it is permanent
+        pub fn remaining(&self) -> Option<Duration> {
+            self.timer
+                .as_ref()
+                .map(|timer| timer.duration().saturating_sub(timer.elapsed()))
+        }
+    }
+
+    impl Default for Disabled {
+        fn default() -> Self {
+            Self::permanent()
+        }
+    }
+
+    /// Removes temporary `Disabled` components once they have expired
+    pub fn tick_disabled(
+        mut commands: Commands,
+        mut query: Query<(Entity, &mut Disabled)>,
+        time: Res<Time>,
+        timestep: Option<Res<AbilityTimestep>>,
+    ) {
+        let delta = ability_delta(&time, timestep);
+        for (entity, mut disabled) in query.iter_mut() {
+            if let Some(timer) = &mut disabled.timer {
+                if timer.tick(delta).finished() {
+                    commands.entity(entity).remove::<Disabled>();
+                }
+            }
+        }
+    }
+
+    pub fn check_for_disabled_abilities(mut query: Query<&mut Usable, With<Disabled>>) {
+        for mut usable in query.iter_mut() {
+            usable.block(UsabilityReason::Disabled);
+        }
+    }
+
+    /// The `GateCheck` matching `check_for_disabled_abilities`
+    pub fn disabled_gate(
+        world: &World,
+        _caster: Entity,
+        ability: Entity,
+    ) -> Option<UsabilityReason> {
+        world
+            .get::<Disabled>(ability)
+            .map(|_| UsabilityReason::Disabled)
+    }
+}
+
+pub mod range {
+    use super::*;
+
+    /// The maximum distance between the caster and the target of an ability
+    ///
+    /// Only checked for `AbilityTarget::Entity` and `AbilityTarget::Point` targets.
+    #[derive(Component, Clone, Copy, PartialEq, Debug)]
+    pub struct Range(pub f32);
+
+    /// Marks abilities as unusable when the `CurrentTarget` of their unit is out of `Range`
+    pub fn check_range(
+        unit_query: Query<(&Abilities, &CurrentTarget, &GlobalTransform)>,
+        transform_query: Query<&GlobalTransform>,
+        mut ability_query: Query<(&Range, &mut Usable), With<Ability>>,
+    ) {
+        for (abilities, current_target, caster_transform) in unit_query.iter() {
+            let caster_position = caster_transform.translation;
+            let distance = match current_target.0 {
+                AbilityTarget::Entity(entity) => match transform_query.get(entity) {
+                    Ok(target_transform) => caster_position.distance(target_transform.translation),
+                    // Targets without a position can never be reached
+                    Err(_) => f32::INFINITY,
+                },
+                AbilityTarget::Point(point) => caster_position.distance(point),
+                AbilityTarget::Direction(_) | AbilityTarget::None => continue,
+            };
+
+            for ability_entity in abilities.ability_list() {
+                if let Ok((&Range(range), mut usable)) = ability_query.get_mut(ability_entity) {
+                    if distance > range {
+                        usable.block(UsabilityReason::OutOfRange);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The `GateCheck` matching `check_range`
+    pub fn range_gate(world: &World, caster: Entity, ability: Entity) -> Option<UsabilityReason> {
+        let &Range(range) = world.get::<Range>(ability)?;
+        let caster_position = world.get::<GlobalTransform>(caster)?.translation;
+        let distance = match world.get::<CurrentTarget>(caster)?.0 {
+            AbilityTarget::Entity(entity) => world
+                .get::<GlobalTransform>(entity)
+                .map_or(f32::INFINITY, |target_transform| {
+                    caster_position.distance(target_transform.translation)
+                }),
+            AbilityTarget::Point(point) => caster_position.distance(point),
+            AbilityTarget::Direction(_) | AbilityTarget::None => return None,
+        };
+
+        if distance > range {
+            Some(UsabilityReason::OutOfRange)
+        } else {
+            None
+        }
+    }
+}
+
+pub mod prerequisites {
+    use super::*;
+
+    /// This ability can only be used while the unit is using another ability, such as "detonate" for a placed mine
+    ///
+    /// The required ability may be active in any `AbilityCategory`,
+    /// or be a `Toggleable` ability that is switched on.
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct RequiresActive(pub Entity);
+
+    /// Marks abilities as unusable when the ability they require is not active for their unit
+    pub fn check_required_abilities(
+        unit_query: Query<&Abilities>,
+        mut ability_query: Query<(&RequiresActive, &mut Usable), With<Ability>>,
+    ) {
+        for abilities in unit_query.iter() {
+            for ability_entity in abilities.ability_list() {
+                if let Ok((&RequiresActive(required), mut usable)) =
+                    ability_query.get_mut(ability_entity)
+                {
+                    let required_is_active = abilities
+                        .active_abilities()
+                        .any(|active_ability| active_ability.entity == Some(required))
+                        || abilities.is_toggled_on(required);
+
+                    if !required_is_active {
+                        usable.block(UsabilityReason::PrerequisiteInactive);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The `GateCheck` matching `check_required_abilities`
+    pub fn required_ability_gate(
+        world: &World,
+        caster: Entity,
+        ability: Entity,
+    ) -> Option<UsabilityReason> {
+        let &RequiresActive(required) = world.get::<RequiresActive>(ability)?;
+        let abilities = world.get::<Abilities>(caster)?;
+        let required_is_active = abilities
+            .active_abilities()
+            .any(|active_ability| active_ability.entity == Some(required))
+            || abilities.is_toggled_on(required);
+
+        if required_is_active {
+            None
+        } else {
+            Some(UsabilityReason::PrerequisiteInactive)
+        }
+    }
+}
+
+pub mod movement {
+    use super::*;
+
+    /// Marker component for units which are currently moving
+    ///
+    /// Stored on the unit entity, alongside its `Abilities`.
+    /// Insert and remove this from your own movement or physics systems,
+    /// so that this crate does not need to know how units move.
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+    pub struct IsMoving;
+
+    /// Marker component for abilities which can only be used while their unit is standing still,
+    /// like turret modes and channeled spells
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+    pub struct RequiresStationary;
+
+    /// Marker component for abilities which can only be used while their unit is `IsMoving`, like sprints
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+    pub struct RequiresMoving;
+
+    /// Marks abilities as unusable when their unit is moving (or stationary) and they require the opposite
+    ///
+    /// ```rust
+    /// use bevy::ecs::system::CommandQueue;
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::movement::{check_movement, IsMoving, RequiresMoving, RequiresStationary};
+    /// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+    /// use leafwing_abilities::abilities::systems::set_all_abilities_usable;
+    /// use leafwing_abilities::abilities::usability::{UsabilityReason, Usable};
+    /// use leafwing_abilities::abilities::Abilities;
+    ///
+    /// let mut world = World::new();
+    /// let mut command_queue = CommandQueue::default();
+    /// let mut commands = Commands::new(&mut command_queue, &world);
+    /// let deploy_turret = commands.spawn_ability().with(RequiresStationary).id();
+    /// let sprint = commands.spawn_ability().with(RequiresMoving).id();
+    /// command_queue.apply(&mut world);
+    ///
+    /// let caster = world
+    ///     .spawn()
+    ///     .insert(Abilities::from_ability_list(vec![deploy_turret, sprint]))
+    ///     .id();
+    ///
+    /// let mut reset = SystemStage::single(set_all_abilities_usable);
+    /// let mut check = SystemStage::single(check_movement);
+    /// let reason = |world: &World, ability| world.get::<Usable>(ability).unwrap().reason();
+    ///
+    /// reset.run(&mut world);
+    /// check.run(&mut world);
+    /// assert_eq!(reason(&world, deploy_turret), UsabilityReason::Ready);
+    /// assert_eq!(reason(&world, sprint), UsabilityReason::WrongMovementState);
+    ///
+    /// world.entity_mut(caster).insert(IsMoving);
+    /// reset.run(&mut world);
+    /// check.run(&mut world);
+    /// assert_eq!(reason(&world, deploy_turret), UsabilityReason::WrongMovementState);
+    /// assert_eq!(reason(&world, sprint), UsabilityReason::Ready);
+    /// ```
+    pub fn check_movement(
+        unit_query: Query<(&Abilities, Option<&IsMoving>)>,
+        mut ability_query: Query<
+            (
+                Option<&RequiresStationary>,
+                Option<&RequiresMoving>,
+                &mut Usable,
+            ),
+            With<Ability>,
+        >,
+    ) {
+        for (abilities, is_moving) in unit_query.iter() {
+            let is_moving = is_moving.is_some();
+
+            for ability_entity in abilities.ability_list() {
+                if let Ok((requires_stationary, requires_moving, mut usable)) =
+                    ability_query.get_mut(ability_entity)
+                {
+                    if wrong_movement_state(is_moving, requires_stationary, requires_moving) {
+                        usable.block(UsabilityReason::WrongMovementState);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The `GateCheck` matching `check_movement`
+    pub fn movement_gate(
+        world: &World,
+        caster: Entity,
+        ability: Entity,
+    ) -> Option<UsabilityReason> {
+        let wrong_state = wrong_movement_state(
+            world.get::<IsMoving>(caster).is_some(),
+            world.get::<RequiresStationary>(ability),
+            world.get::<RequiresMoving>(ability),
+        );
+
+        if wrong_state {
+            Some(UsabilityReason::WrongMovementState)
+        } else {
+            None
+        }
+    }
+
+    fn wrong_movement_state(
+        is_moving: bool,
+        requires_stationary: Option<&RequiresStationary>,
+        requires_moving: Option<&RequiresMoving>,
+    ) -> bool {
+        (is_moving && requires_stationary.is_some()) || (!is_moving && requires_moving.is_some())
+    }
+}
+
+pub mod ammo {
+    use super::*;
+
+    /// A limited number of uses, which are only restored by gameplay such as pickups
+    ///
+    /// Unlike the charges of a `Cooldown`, ammo never regenerates over time.
+    /// Abilities with both are only usable when they have ammo *and* a cooldown charge,
+    /// and each use consumes one of each.
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct AbilityAmmo {
+        pub current: u32,
+        pub max: u32,
+    }
+
+    impl AbilityAmmo {
+        /// Creates a full supply of ammo
+        pub fn new(max: u32) -> Self {
+            Self { current: max, max }
+        }
+
+        /// Restores up to `amount` uses, without exceeding the maximum
+        pub fn refill(&mut self, amount: u32) {
+            self.current = self.current.saturating_add(amount).min(self.max);
+        }
+
+        /// Consumes a single use, returning `false` if none were left
+        pub fn spend(&mut self) -> bool {
+            if self.current == 0 {
+                false
+            } else {
+                self.current -= 1;
+                true
+            }
+        }
+    }
+
+    pub fn check_ammo(mut query: Query<(&AbilityAmmo, &mut Usable), With<Ability>>) {
+        for (ammo, mut usable) in query.iter_mut() {
+            if ammo.current == 0 {
+                usable.block(UsabilityReason::NoAmmo);
+            }
+        }
+    }
+
+    /// The `GateCheck` matching `check_ammo`
+    pub fn ammo_gate(world: &World, _caster: Entity, ability: Entity) -> Option<UsabilityReason> {
+        if world.get::<AbilityAmmo>(ability)?.current == 0 {
+            Some(UsabilityReason::NoAmmo)
+        } else {
+            None
+        }
+    }
+
+    /// Consumes the ammo of each ability that was just used
+    pub fn spend_ammo(
+        unit_query: Query<&Abilities>,
+        mut ammo_query: Query<&mut AbilityAmmo, With<Ability>>,
+    ) {
+        for abilities in unit_query.iter() {
+            for active_ability in abilities.active_abilities() {
+                if active_ability.state != AbilityState::JustStarted {
+                    continue;
+                }
+
+                if let Some(ability_entity) = active_ability.entity {
+                    if let Ok(mut ammo) = ammo_query.get_mut(ability_entity) {
+                        ammo.spend();
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub mod empowerment {
+    use super::*;
+
+    /// Stacks banked by a unit, to be consumed by its next ability with `ConsumesEmpowerment`
+    ///
+    /// Stored on the unit entity, alongside its `Abilities`.
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+    pub struct Empowerment {
+        stacks: u32,
+        /// If set, all stacks are lost once this much time passes without gaining more
+        window: Option<Duration>,
+        since_last_gained: Duration,
+    }
+
+    impl Empowerment {
+        /// Creates an empty stack store, whose stacks never decay
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// All stacks are lost once `window` passes without gaining more
+        pub fn with_decay(mut self, window: Duration) -> Self {
+            self.window = Some(window);
+            self
+        }
+
+        pub fn stacks(&self) -> u32 {
+            self.stacks
+        }
+
+        pub fn add(&mut self, stacks: u32) {
+            self.stacks = self.stacks.saturating_add(stacks);
+            self.since_last_gained = Duration::ZERO;
+        }
+
+        /// Removes and returns all stacks
+        pub fn consume(&mut self) -> u32 {
+            core::mem::take(&mut self.stacks)
+        }
+
+        pub fn tick(&mut self, delta: Duration) {
+            if self.stacks == 0 {
+                return;
+            }
+
+            self.since_last_gained += delta;
+            if let Some(window) = self.window {
+                if self.since_last_gained >= window {
+                    self.stacks = 0;
+                }
+            }
+        }
+    }
+
+    /// Abilities which add stacks of `Empowerment` to their caster when used
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct GrantsEmpowerment(pub u32);
+
+    /// Marker component for abilities which consume all of their caster's `Empowerment` when used
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct ConsumesEmpowerment;
+
+    /// The number of `Empowerment` stacks consumed the last time this ability was used
+    ///
+    /// Read this in your effect systems to scale the ability.
+    #[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct ConsumedEmpowerment(pub u32);
+
+    pub fn decay_empowerment(
+        mut query: Query<&mut Empowerment>,
+        time: Res<Time>,
+        timestep: Option<Res<AbilityTimestep>>,
+    ) {
+        let delta = ability_delta(&time, timestep);
+        for mut empowerment in query.iter_mut() {
+            // Extra check here avoids change-detection false positives
+            if empowerment.stacks() > 0 {
+                empowerment.tick(delta);
+            }
+        }
+    }
+
+    /// Consumes and grants `Empowerment` for each ability that was just used
+    ///
+    /// Stacks are consumed before they are granted,
+    /// so abilities which do both are empowered by the stacks banked before they were used.
+    pub fn apply_empowerment(
+        mut commands: Commands,
+        mut unit_query: Query<(&Abilities, &mut Empowerment)>,
+        ability_query: Query<
+            (Option<&GrantsEmpowerment>, Option<&ConsumesEmpowerment>),
+            With<Ability>,
+        >,
+    ) {
+        for (abilities, mut empowerment) in unit_query.iter_mut() {
+            for active_ability in abilities.active_abilities() {
+                if active_ability.state != AbilityState::JustStarted {
+                    continue;
+                }
+
+                let ability_entity = match active_ability.entity {
+                    Some(ability_entity) => ability_entity,
+                    None => continue,
+                };
+
+                if let Ok((grants, consumes)) = ability_query.get(ability_entity) {
+                    if consumes.is_some() {
+                        let stacks = empowerment.consume();
+                        commands
+                            .entity(ability_entity)
+                            .insert(ConsumedEmpowerment(stacks));
+                    }
+
+                    if let Some(&GrantsEmpowerment(stacks)) = grants {
+                        empowerment.add(stacks);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub mod diminishing_returns {
+    use super::*;
+
+    /// Weakens an ability each time it is used again within `window` of its previous uses, to discourage spam
+    ///
+    /// Effect and cost systems should scale the ability by `DiminishingReturns::multiplier`,
+    /// which is `factor` raised to the number of earlier uses still within the window.
+    /// Uses are recorded after `AbilityLabel::Effect`, so the use currently taking effect is not counted.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::diminishing_returns::DiminishingReturns;
+    ///
+    /// let mut stun = DiminishingReturns::new(Duration::from_secs(15), 0.5);
+    /// assert_eq!(stun.multiplier(), 1.0);
+    ///
+    /// stun.record_use();
+    /// stun.tick(Duration::from_secs(5));
+    /// stun.record_use();
+    /// // The third stun in 15 seconds lasts a quarter as long
+    /// assert_eq!(stun.multiplier(), 0.25);
+    ///
+    /// // The first use falls out of the window
+    /// stun.tick(Duration::from_secs(11));
+    /// assert_eq!(stun.uses_in_window(), 1);
+    /// assert_eq!(stun.multiplier(), 0.5);
+    /// ```
+    #[derive(Component, Clone, PartialEq, Debug)]
+    pub struct DiminishingReturns {
+        pub window: Duration,
+        pub factor: f32,
+        /// The time since each recent use, from oldest to newest
+        recent_uses: Vec<Duration>,
+    }
+
+    impl DiminishingReturns {
+        pub fn new(window: Duration, factor: f32) -> Self {
+            Self {
+                window,
+                factor,
+                recent_uses: Vec::default(),
+            }
+        }
+
+        /// The number of uses that are still within the window
+        pub fn uses_in_window(&self) -> usize {
+            self.recent_uses.len()
+        }
+
+        /// The multiplier that should be applied to the effect or cost of the next use
+        pub fn multiplier(&self) -> f32 {
+            self.factor.powi(self.recent_uses.len() as i32)
+        }
+
+        pub fn record_use(&mut self) {
+            self.recent_uses.push(Duration::ZERO);
+        }
+
+        /// Ages each recent use, forgetting those which are older than the window
+        pub fn tick(&mut self, delta: Duration) {
+            let window = self.window;
+            for age in self.recent_uses.iter_mut() {
+                *age += delta;
+            }
+            // Uses are stored from oldest to newest, so expired uses are always at the front
+            let expired = self
+                .recent_uses
+                .iter()
+                .take_while(|&&age| age >= window)
+                .count();
+            self.recent_uses.drain(..expired);
+        }
+    }
+
+    /// Forgets uses of abilities with `DiminishingReturns` once they fall out of the window
+    pub fn tick_diminishing_returns(
+        mut query: Query<&mut DiminishingReturns, With<Ability>>,
+        time: Res<Time>,
+        timestep: Option<Res<AbilityTimestep>>,
+    ) {
+        let delta = ability_delta(&time, timestep);
+        for mut diminishing_returns in query.iter_mut() {
+            // Extra check here avoids change-detection false positives
+            if diminishing_returns.uses_in_window() > 0 {
+                diminishing_returns.tick(delta);
+            }
+        }
+    }
+
+    /// Records a use of each ability with `DiminishingReturns` that was just used
+    pub fn record_diminishing_returns(
+        unit_query: Query<&Abilities>,
+        mut ability_query: Query<&mut DiminishingReturns, With<Ability>>,
+    ) {
+        for abilities in unit_query.iter() {
+            for active_ability in abilities.active_abilities() {
+                if active_ability.state != AbilityState::JustStarted {
+                    continue;
+                }
+
+                if let Some(ability_entity) = active_ability.entity {
+                    if let Ok(mut diminishing_returns) = ability_query.get_mut(ability_entity) {
+                        diminishing_returns.record_use();
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub mod passive {
+    use super::*;
+
+    /// Marker component for abilities which are always in effect, like auras or thorns
+    ///
+    /// Passive abilities are never chosen by an `AbilityInputMap`, and do not need cooldowns.
+    /// Effect systems should use `passive_abilities` to find them,
+    /// and any `Channeled` drain they have is paid for as long as it can be afforded.
+    ///
+    /// ```rust
+    /// use bevy::ecs::system::CommandQueue;
+    /// use bevy::prelude::*;
+    /// use bevy::utils::HashMap;
+    /// use leafwing_abilities::abilities::ability_mapping::{AbilityInputMap, SimpleAbilityMap};
+    /// use leafwing_abilities::abilities::passive::{check_for_passive_abilities, Passive};
+    /// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+    /// use leafwing_abilities::abilities::systems::set_all_abilities_usable;
+    /// use leafwing_abilities::abilities::usability::Usable;
+    /// use leafwing_input_manager::prelude::*;
+    ///
+    /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+    /// enum Action {
+    ///     Thorns,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let mut command_queue = CommandQueue::default();
+    /// let mut commands = Commands::new(&mut command_queue, &world);
+    /// let thorns = commands.spawn_ability().with(Passive).id();
+    /// command_queue.apply(&mut world);
+    ///
+    /// SystemStage::single(set_all_abilities_usable).run(&mut world);
+    /// SystemStage::single(check_for_passive_abilities).run(&mut world);
+    /// let mut usable = HashMap::default();
+    /// usable.insert(thorns, world.get::<Usable>(thorns).unwrap().is_usable());
+    ///
+    /// let mut bindings = HashMap::default();
+    /// bindings.insert(Action::Thorns, thorns);
+    /// let mut map = SimpleAbilityMap::new(bindings);
+    ///
+    /// // Even when bound to an input, passive abilities are never chosen
+    /// let mut action_state = ActionState::<Action>::default();
+    /// action_state.press(Action::Thorns);
+    /// assert_eq!(map.process_input(&action_state, usable), None);
+    /// ```
+    #[derive(Component, Clone, Copy)]
+    pub struct Passive;
+
+    /// Passive abilities are never usable, so that they cannot be chosen
+    pub fn check_for_passive_abilities(mut query: Query<&mut Usable, With<Passive>>) {
+        for mut usable in query.iter_mut() {
+            usable.block(UsabilityReason::Passive);
+        }
+    }
+
+    /// The `GateCheck` matching `check_for_passive_abilities`
+    pub fn passive_gate(
+        world: &World,
+        _caster: Entity,
+        ability: Entity,
+    ) -> Option<UsabilityReason> {
+        world
+            .get::<Passive>(ability)
+            .map(|_| UsabilityReason::Passive)
+    }
+
+    /// The passive abilities of the provided unit
+    pub fn passive_abilities(
+        abilities: &Abilities,
+        passive_query: &Query<Entity, (With<Ability>, With<Passive>)>,
+    ) -> Vec<Entity> {
+        abilities
+            .ability_list()
+            .into_iter()
+            .filter(|&ability_entity| passive_query.get(ability_entity).is_ok())
+            .collect()
+    }
+}
+
+pub mod queue {
+    use super::*;
+
+    /// How urgently an ability should be used when it is queued
+    ///
+    /// Abilities without this component have a priority of 0.
+    #[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+    pub struct AbilityPriority(pub u8);
+
+    /// Stores the abilities chosen by a unit, so that urgent abilities can jump ahead of others
+    ///
+    /// Stored on the unit entity, alongside its `Abilities`, and used in place of its input buffer.
+    /// Every chosen ability is queued: once its category is free, the highest-priority usable entry is started,
+    /// and every other entry of that category is discarded.
+    /// Entries which have waited longer than the window are dropped.
+    #[derive(Component, Clone, PartialEq, Debug)]
+    pub struct AbilityQueue {
+        window: Duration,
+        entries: Vec<QueuedAbility>,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    struct QueuedAbility {
+        ability: Entity,
+        priority: AbilityPriority,
+        age: Duration,
+    }
+
+    impl AbilityQueue {
+        pub fn new(window: Duration) -> Self {
+            Self {
+                window,
+                entries: Vec::default(),
+            }
+        }
+
+        /// Queues the ability, replacing any existing entry for it
+        pub fn push(&mut self, ability: Entity, priority: AbilityPriority) {
+            self.remove(ability);
+            self.entries.push(QueuedAbility {
+                ability,
+                priority,
+                age: Duration::ZERO,
+            });
+        }
+
+        pub fn remove(&mut self, ability: Entity) {
+            self.entries.retain(|entry| entry.ability != ability);
+        }
+
+        /// The queued abilities, in the order they were queued
+        pub fn queued(&self) -> Vec<Entity> {
+            self.entries.iter().map(|entry| entry.ability).collect()
+        }
+
+        /// The highest-priority ability for which `eligible` returns true
+        ///
+        /// Ties are broken in favor of the ability that was queued first.
+        pub fn highest(&self, mut eligible: impl FnMut(Entity) -> bool) -> Option<Entity> {
+            let mut highest: Option<QueuedAbility> = None;
+            for &entry in self.entries.iter() {
+                if !eligible(entry.ability) {
+                    continue;
+                }
+
+                if highest.map_or(true, |highest| entry.priority > highest.priority) {
+                    highest = Some(entry);
+                }
+            }
+            highest.map(|entry| entry.ability)
+        }
+
+        /// Discards every entry for which `discard` returns true
+        pub fn discard(&mut self, mut discard: impl FnMut(Entity) -> bool) {
+            self.entries.retain(|entry| !discard(entry.ability));
+        }
+
+        /// Ages each entry, dropping those which have waited longer than the window
+        pub fn tick(&mut self, delta: Duration) {
+            let window = self.window;
+            for entry in self.entries.iter_mut() {
+                entry.age += delta;
+            }
+            self.entries.retain(|entry| entry.age <= window);
+        }
+    }
+}
+
+pub mod ability_mapping {
+    use super::casting::{initial_state, CastTime};
+    use super::queue::{AbilityPriority, AbilityQueue};
+    use super::*;
+    use bevy::utils::HashMap;
+    use leafwing_input_manager::prelude::{ActionState, Actionlike};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    /// Used for deciding which ability the character should use, given the inputs received
+    ///
+    /// Inputs are described by the user-defined `Actionlike` type `A`.
+    /// Maps must be `Clone`, so that configured units can be duplicated along with their `AbilityMap`.
+    pub trait AbilityInputMap<A: Actionlike>:
+        CloneAbilityInputMap<A> + Send + Sync + 'static
+    {
+        /// Spawns an ability entity,
+        /// and returns its entity if and only if an ability was selected
+        ///
+        /// Maps may update their internal state here, such as progress through an input sequence.
+        fn process_input(
+            &mut self,
+            _action_state: &ActionState<A>,
+            usable: HashMap<Entity, bool>,
+        ) -> Option<Entity>;
+
+        fn ability_list(&self) -> Vec<Entity>;
+
+        /// Advances any time-dependent internal state, once per frame
+        fn tick(&mut self, _delta: Duration) {}
+
+        /// How charged the provided ability was when it was last chosen, from 0.0 to 1.0
+        fn charge(&self, _ability: Entity) -> f32 {
+            1.0
+        }
+
+        /// Each input and the ability it triggers, such as for rebinding menus and tooltips
+        ///
+        /// Maps whose abilities are not bound to a single input, like chords and sequences, describe no bindings.
+        fn describe_bindings(&self) -> Vec<(A, Entity)> {
+            Vec::default()
+        }
+    }
+
+    /// Clones boxed `AbilityInputMap`s
+    ///
+    /// This is implemented automatically for every `AbilityInputMap` which is `Clone`.
+    pub trait CloneAbilityInputMap<A: Actionlike> {
+        fn clone_box(&self) -> Box<dyn AbilityInputMap<A>>;
+    }
+
+    impl<A: Actionlike, M: AbilityInputMap<A> + Clone> CloneAbilityInputMap<A> for M {
+        fn clone_box(&self) -> Box<dyn AbilityInputMap<A>> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// Abilities do not respond to inputs
+    ///
+    /// Used for NPCs
+    #[derive(Default, Clone)]
+    pub struct NullAbilityMap;
+
+    impl<A: Actionlike> AbilityInputMap<A> for NullAbilityMap {
+        fn process_input(
+            &mut self,
+            _action_state: &ActionState<A>,
+            _usable: HashMap<Entity, bool>,
+        ) -> Option<Entity> {
+            None
+        }
+
+        fn ability_list(&self) -> Vec<Entity> {
+            Vec::default()
+        }
+    }
+
+    /// Only one ability can be used at once,
+    /// and each ability corresponds to one input
+    ///
+    /// Abilities are triggered when their input is pressed, unless configured otherwise with `with_trigger`.
+    #[derive(Clone)]
+    pub struct SimpleAbilityMap<A: Actionlike> {
+        map: HashMap<A, Entity>,
+        triggers: HashMap<A, TriggerMode>,
+    }
+
+    /// When the input of an ability in a `SimpleAbilityMap` triggers it
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    pub enum TriggerMode {
+        /// On the frame the input is pressed
+        OnPress,
+        /// On the frame the input is released, such as for charged shots
+        OnRelease,
+        /// On every frame that the input is held
+        OnHold,
+    }
+
+    impl Default for TriggerMode {
+        fn default() -> Self {
+            TriggerMode::OnPress
+        }
+    }
+
+    impl TriggerMode {
+        /// Whether the provided action triggers its ability this frame
+        pub fn triggered<A: Actionlike>(&self, action_state: &ActionState<A>, action: A) -> bool {
+            match self {
+                TriggerMode::OnPress => action_state.just_pressed(action),
+                TriggerMode::OnRelease => action_state.just_released(action),
+                TriggerMode::OnHold => action_state.pressed(action),
+            }
+        }
+    }
+
+    impl<A: Actionlike> AbilityInputMap<A> for SimpleAbilityMap<A> {
+        fn process_input(
+            &mut self,
+            action_state: &ActionState<A>,
+            usable: HashMap<Entity, bool>,
+        ) -> Option<Entity> {
+            for action in A::variants() {
+                if self.trigger(action).triggered(action_state, action) {
+                    // Not every action corresponds to an ability
+                    if let Some(&ability_entity) = self.map.get(&action) {
+                        // Only attempt to use abilities if they can currently be used
+                        // If they can't, try another matching ability
+                        if usable.get(&ability_entity) == Some(&true) {
+                            return Some(ability_entity);
+                        }
+                    }
+                }
+            }
+            None
+        }
+
+        fn ability_list(&self) -> Vec<Entity> {
+            self.map.values().cloned().collect()
+        }
+
+        fn describe_bindings(&self) -> Vec<(A, Entity)> {
+            A::variants()
+                .filter_map(|action| Some((action, *self.map.get(&action)?)))
+                .collect()
+        }
+    }
+
+    impl<A: Actionlike> SimpleAbilityMap<A> {
+        pub fn new(map: HashMap<A, Entity>) -> Self {
+            Self {
+                map,
+                triggers: HashMap::default(),
+            }
+        }
+
+        /// Sets when the provided input triggers its ability
+        ///
+        /// ```rust
+        /// use bevy::prelude::*;
+        /// use bevy::utils::HashMap;
+        /// use leafwing_abilities::abilities::ability_mapping::{
+        ///     AbilityInputMap, SimpleAbilityMap, TriggerMode,
+        /// };
+        /// use leafwing_input_manager::prelude::*;
+        ///
+        /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+        /// enum Action {
+        ///     Fireball,
+        ///     ChargedShot,
+        ///     Flamethrower,
+        /// }
+        ///
+        /// let fireball = Entity::from_raw(0);
+        /// let charged_shot = Entity::from_raw(1);
+        /// let flamethrower = Entity::from_raw(2);
+        ///
+        /// let mut map = HashMap::default();
+        /// map.insert(Action::Fireball, fireball);
+        /// map.insert(Action::ChargedShot, charged_shot);
+        /// map.insert(Action::Flamethrower, flamethrower);
+        /// let mut map = SimpleAbilityMap::new(map)
+        ///     .with_trigger(Action::ChargedShot, TriggerMode::OnRelease)
+        ///     .with_trigger(Action::Flamethrower, TriggerMode::OnHold);
+        /// assert_eq!(map.trigger(Action::Fireball), TriggerMode::OnPress);
+        ///
+        /// let mut usable = HashMap::default();
+        /// for ability in [fireball, charged_shot, flamethrower] {
+        ///     usable.insert(ability, true);
+        /// }
+        ///
+        /// // Pressing triggers `OnPress` abilities
+        /// let mut action_state = ActionState::<Action>::default();
+        /// action_state.press(Action::Fireball);
+        /// assert_eq!(map.process_input(&action_state, usable.clone()), Some(fireball));
+        ///
+        /// // Pressing does not trigger `OnRelease` abilities, but releasing does
+        /// let mut action_state = ActionState::<Action>::default();
+        /// action_state.press(Action::ChargedShot);
+        /// assert_eq!(map.process_input(&action_state, usable.clone()), None);
+        /// action_state.release(Action::ChargedShot);
+        /// assert_eq!(map.process_input(&action_state, usable.clone()), Some(charged_shot));
+        ///
+        /// // `OnHold` abilities are triggered every frame while their input is held
+        /// let mut action_state = ActionState::<Action>::default();
+        /// action_state.press(Action::Flamethrower);
+        /// assert_eq!(map.process_input(&action_state, usable.clone()), Some(flamethrower));
+        /// assert_eq!(map.process_input(&action_state, usable.clone()), Some(flamethrower));
+        /// action_state.release(Action::Flamethrower);
+        /// assert_eq!(map.process_input(&action_state, usable), None);
+        /// ```
+        pub fn with_trigger(mut self, action: A, trigger: TriggerMode) -> Self {
+            self.triggers.insert(action, trigger);
+            self
+        }
+
+        /// When the provided input triggers its ability
+        pub fn trigger(&self, action: A) -> TriggerMode {
+            self.triggers.get(&action).copied().unwrap_or_default()
+        }
+
+        /// The ability triggered by the provided input, if any
+        ///
+        /// ```rust
+        /// use bevy::prelude::*;
+        /// use bevy::utils::HashMap;
+        /// use leafwing_abilities::abilities::ability_mapping::{AbilityInputMap, SimpleAbilityMap};
+        /// use leafwing_input_manager::prelude::*;
+        ///
+        /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+        /// enum Action {
+        ///     Fireball,
+        ///     Jump,
+        /// }
+        ///
+        /// let fireball = Entity::from_raw(0);
+        /// let mut map = HashMap::default();
+        /// map.insert(Action::Fireball, fireball);
+        /// let map = SimpleAbilityMap::new(map);
+        ///
+        /// assert_eq!(map.ability_for(Action::Fireball), Some(fireball));
+        /// assert_eq!(map.ability_for(Action::Jump), None);
+        /// assert_eq!(map.input_for(fireball), Some(Action::Fireball));
+        /// assert_eq!(map.input_for(Entity::from_raw(1)), None);
+        /// assert_eq!(
+        ///     AbilityInputMap::<Action>::describe_bindings(&map),
+        ///     vec![(Action::Fireball, fireball)]
+        /// );
+        /// ```
+        pub fn ability_for(&self, action: A) -> Option<Entity> {
+            self.map.get(&action).copied()
+        }
+
+        /// The first input, in the order of `Actionlike::variants`, that triggers the provided ability
+        pub fn input_for(&self, ability: Entity) -> Option<A> {
+            A::variants().find(|action| self.map.get(action) == Some(&ability))
+        }
+    }
+
+    impl<A: Actionlike> Default for SimpleAbilityMap<A> {
+        fn default() -> Self {
+            Self::new(HashMap::default())
+        }
+    }
+
+    /// Each ability is triggered by a chord of inputs, which must all be held at once
+    ///
+    /// When several chords match, larger chords take priority, so Shift + Q is preferred over Q.
+    #[derive(Clone)]
+    pub struct ChordAbilityMap<A: Actionlike> {
+        /// Sorted from largest to smallest
+        chords: Vec<(Vec<A>, Entity)>,
+    }
+
+    impl<A: Actionlike> AbilityInputMap<A> for ChordAbilityMap<A> {
+        fn process_input(
+            &mut self,
+            action_state: &ActionState<A>,
+            usable: HashMap<Entity, bool>,
+        ) -> Option<Entity> {
+            for (chord, ability_entity) in self.chords.iter() {
+                // The chord must be completed this frame, rather than merely held
+                let held = chord.iter().all(|&action| action_state.pressed(action));
+                let completed = chord
+                    .iter()
+                    .any(|&action| action_state.just_pressed(action));
+
+                // Only attempt to use abilities if they can currently be used
+                // If they can't, try another matching ability
+                if held && completed && usable.get(ability_entity) == Some(&true) {
+                    return Some(*ability_entity);
+                }
+            }
+            None
+        }
+
+        fn ability_list(&self) -> Vec<Entity> {
+            self.chords
+                .iter()
+                .map(|(_chord, ability_entity)| *ability_entity)
+                .collect()
+        }
+    }
+
+    impl<A: Actionlike> ChordAbilityMap<A> {
+        /// ```rust
+        /// use bevy::prelude::*;
+        /// use bevy::utils::HashMap;
+        /// use leafwing_abilities::abilities::ability_mapping::{AbilityInputMap, ChordAbilityMap};
+        /// use leafwing_input_manager::prelude::*;
+        ///
+        /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+        /// enum Action {
+        ///     Shift,
+        ///     Q,
+        /// }
+        ///
+        /// let strike = Entity::from_raw(0);
+        /// let heavy_strike = Entity::from_raw(1);
+        /// let mut map = ChordAbilityMap::new(vec![
+        ///     (vec![Action::Q], strike),
+        ///     (vec![Action::Shift, Action::Q], heavy_strike),
+        /// ]);
+        ///
+        /// let mut usable = HashMap::default();
+        /// usable.insert(strike, true);
+        /// usable.insert(heavy_strike, true);
+        ///
+        /// // Both chords are held, so the larger one wins
+        /// let mut action_state = ActionState::<Action>::default();
+        /// action_state.press(Action::Shift);
+        /// action_state.press(Action::Q);
+        /// assert_eq!(map.process_input(&action_state, usable.clone()), Some(heavy_strike));
+        ///
+        /// // Chords which cannot be used are skipped in favor of smaller matching chords
+        /// usable.insert(heavy_strike, false);
+        /// assert_eq!(map.process_input(&action_state, usable), Some(strike));
+        /// ```
+        pub fn new(mut chords: Vec<(Vec<A>, Entity)>) -> Self {
+            // Sorting is stable, so chords of the same size keep their relative order
+            chords.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+            Self { chords }
+        }
+    }
+
+    impl<A: Actionlike> Default for ChordAbilityMap<A> {
+        fn default() -> Self {
+            Self {
+                chords: Vec::default(),
             }
         }
     }
-}
 
-pub mod disabled {
-    use super::*;
+    /// Abilities are triggered by pressing a sequence of inputs in order,
+    /// like the special moves of fighting games
+    ///
+    /// Each step of a sequence must be pressed within its timeout of the previous step,
+    /// or the sequence must be started again.
+    #[derive(Clone)]
+    pub struct SequenceAbilityMap<A: Actionlike> {
+        sequences: Vec<InputSequence<A>>,
+    }
 
-    /// Marker component for abilities which cannot be used for miscallaneous reasons
-    #[derive(Component)]
-    pub struct Disabled;
+    #[derive(Clone)]
+    struct InputSequence<A: Actionlike> {
+        steps: Vec<A>,
+        step_timeout: Duration,
+        ability: Entity,
+        /// The index of the next step that must be pressed
+        progress: usize,
+        since_last_step: Duration,
+    }
 
-    pub fn check_for_disabled_abilities(mut query: Query<&mut Usable, With<Disabled>>) {
-        for mut usable in query.iter_mut() {
-            *usable = Usable(false);
+    impl<A: Actionlike> AbilityInputMap<A> for SequenceAbilityMap<A> {
+        fn process_input(
+            &mut self,
+            action_state: &ActionState<A>,
+            usable: HashMap<Entity, bool>,
+        ) -> Option<Entity> {
+            let mut chosen = None;
+
+            // Every sequence must be advanced, even once an ability has been chosen
+            for sequence in self.sequences.iter_mut() {
+                if sequence.steps.is_empty() {
+                    continue;
+                }
+
+                if action_state.just_pressed(sequence.steps[sequence.progress]) {
+                    sequence.progress += 1;
+                    sequence.since_last_step = Duration::ZERO;
+                } else if action_state.just_pressed(sequence.steps[0]) {
+                    sequence.progress = 1;
+                    sequence.since_last_step = Duration::ZERO;
+                }
+
+                if sequence.progress == sequence.steps.len() {
+                    sequence.progress = 0;
+
+                    if chosen.is_none() && usable.get(&sequence.ability) == Some(&true) {
+                        chosen = Some(sequence.ability);
+                    }
+                }
+            }
+            chosen
+        }
+
+        fn ability_list(&self) -> Vec<Entity> {
+            self.sequences
+                .iter()
+                .map(|sequence| sequence.ability)
+                .collect()
+        }
+
+        fn tick(&mut self, delta: Duration) {
+            for sequence in self.sequences.iter_mut() {
+                if sequence.progress == 0 {
+                    continue;
+                }
+
+                sequence.since_last_step += delta;
+                if sequence.since_last_step > sequence.step_timeout {
+                    sequence.progress = 0;
+                }
+            }
         }
     }
-}
 
-pub mod ability_mapping {
-    use super::*;
-    use crate::input::{ActionState, InputAction};
-    use bevy::utils::HashMap;
+    impl<A: Actionlike> SequenceAbilityMap<A> {
+        /// Each sequence is made of its steps, the maximum time allowed between steps, and its ability
+        ///
+        /// ```rust
+        /// use bevy::prelude::*;
+        /// use bevy::utils::HashMap;
+        /// use core::time::Duration;
+        /// use leafwing_abilities::abilities::ability_mapping::{
+        ///     AbilityInputMap, SequenceAbilityMap,
+        /// };
+        /// use leafwing_input_manager::prelude::*;
+        ///
+        /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+        /// enum Action {
+        ///     Down,
+        ///     Forward,
+        ///     Punch,
+        /// }
+        ///
+        /// let hadouken = Entity::from_raw(0);
+        /// let mut map = SequenceAbilityMap::new(vec![(
+        ///     vec![Action::Down, Action::Forward, Action::Punch],
+        ///     Duration::from_millis(200),
+        ///     hadouken,
+        /// )]);
+        /// let mut usable = HashMap::default();
+        /// usable.insert(hadouken, true);
+        ///
+        /// // Advances time, then presses the provided input
+        /// let press = |map: &mut SequenceAbilityMap<Action>, action, millis| {
+        ///     AbilityInputMap::<Action>::tick(map, Duration::from_millis(millis));
+        ///     let mut action_state = ActionState::<Action>::default();
+        ///     action_state.press(action);
+        ///     map.process_input(&action_state, usable.clone())
+        /// };
+        ///
+        /// // Each step is pressed within the window, so the motion completes
+        /// assert_eq!(press(&mut map, Action::Down, 0), None);
+        /// assert_eq!(press(&mut map, Action::Forward, 100), None);
+        /// assert_eq!(press(&mut map, Action::Punch, 100), Some(hadouken));
+        ///
+        /// // The same motion performed too slowly has to be started again
+        /// assert_eq!(press(&mut map, Action::Down, 1000), None);
+        /// assert_eq!(press(&mut map, Action::Forward, 300), None);
+        /// assert_eq!(press(&mut map, Action::Punch, 300), None);
+        /// ```
+        pub fn new(sequences: Vec<(Vec<A>, Duration, Entity)>) -> Self {
+            Self {
+                sequences: sequences
+                    .into_iter()
+                    .map(|(steps, step_timeout, ability)| InputSequence {
+                        steps,
+                        step_timeout,
+                        ability,
+                        progress: 0,
+                        since_last_step: Duration::ZERO,
+                    })
+                    .collect(),
+            }
+        }
+    }
 
-    /// Used for deciding which ability the character should use, given the inputs received
-    pub trait AbilityInputMap: Send + Sync + 'static {
-        /// Spawns an ability entity,
-        /// and returns its entity if and only if an ability was selected
+    impl<A: Actionlike> Default for SequenceAbilityMap<A> {
+        fn default() -> Self {
+            Self {
+                sequences: Vec::default(),
+            }
+        }
+    }
+
+    /// Abilities are charged by holding their input, and used once it is released,
+    /// like drawing a bow
+    ///
+    /// Releasing an input before its minimum charge time cancels the ability.
+    /// The charge level is recorded in `ActiveAbility::charge`,
+    /// scaling from 0.0 at the minimum charge time to 1.0 at the maximum.
+    #[derive(Clone)]
+    pub struct ChargeAbilityMap<A: Actionlike> {
+        bindings: Vec<ChargeBinding<A>>,
+    }
+
+    #[derive(Clone)]
+    struct ChargeBinding<A: Actionlike> {
+        action: A,
+        ability: Entity,
+        min_charge: Duration,
+        max_charge: Duration,
+        /// How long the input has been held, if it is currently held
+        held: Option<Duration>,
+        /// The charge level on the most recent release
+        charge: f32,
+    }
+
+    impl<A: Actionlike> ChargeBinding<A> {
+        fn charge_level(&self, held: Duration) -> f32 {
+            let charge_range = self.max_charge.saturating_sub(self.min_charge);
+            if charge_range == Duration::ZERO {
+                return 1.0;
+            }
+
+            let charged = held.min(self.max_charge).saturating_sub(self.min_charge);
+            charged.as_secs_f32() / charge_range.as_secs_f32()
+        }
+    }
+
+    impl<A: Actionlike> AbilityInputMap<A> for ChargeAbilityMap<A> {
         fn process_input(
-            &self,
-            _action_state: &ActionState,
+            &mut self,
+            action_state: &ActionState<A>,
             usable: HashMap<Entity, bool>,
-        ) -> Option<Entity>;
+        ) -> Option<Entity> {
+            let mut chosen = None;
 
-        fn ability_list(&self) -> Vec<Entity>;
+            // Every binding must be updated, even once an ability has been chosen
+            for binding in self.bindings.iter_mut() {
+                if action_state.pressed(binding.action) {
+                    binding.held.get_or_insert(Duration::ZERO);
+                    continue;
+                }
+
+                if let Some(held) = binding.held.take() {
+                    if held < binding.min_charge {
+                        continue;
+                    }
+
+                    if chosen.is_none() && usable.get(&binding.ability) == Some(&true) {
+                        binding.charge = binding.charge_level(held);
+                        chosen = Some(binding.ability);
+                    }
+                }
+            }
+            chosen
+        }
+
+        fn ability_list(&self) -> Vec<Entity> {
+            self.bindings
+                .iter()
+                .map(|binding| binding.ability)
+                .collect()
+        }
+
+        fn tick(&mut self, delta: Duration) {
+            for binding in self.bindings.iter_mut() {
+                if let Some(held) = &mut binding.held {
+                    *held += delta;
+                }
+            }
+        }
+
+        fn charge(&self, ability: Entity) -> f32 {
+            self.bindings
+                .iter()
+                .find(|binding| binding.ability == ability)
+                .map_or(1.0, |binding| binding.charge)
+        }
+
+        fn describe_bindings(&self) -> Vec<(A, Entity)> {
+            self.bindings
+                .iter()
+                .map(|binding| (binding.action, binding.ability))
+                .collect()
+        }
     }
 
-    /// Abilities do not respond to inputs
+    impl<A: Actionlike> ChargeAbilityMap<A> {
+        /// Each binding is made of its input, its ability, and its minimum and maximum charge times
+        pub fn new(bindings: Vec<(A, Entity, Duration, Duration)>) -> Self {
+            Self {
+                bindings: bindings
+                    .into_iter()
+                    .map(|(action, ability, min_charge, max_charge)| ChargeBinding {
+                        action,
+                        ability,
+                        min_charge,
+                        max_charge,
+                        held: None,
+                        charge: 1.0,
+                    })
+                    .collect(),
+            }
+        }
+    }
+
+    impl<A: Actionlike> Default for ChargeAbilityMap<A> {
+        fn default() -> Self {
+            Self {
+                bindings: Vec::default(),
+            }
+        }
+    }
+
+    /// Abilities are chosen without any input, by using the first usable ability in priority order
     ///
-    /// Used for NPCs
-    #[derive(Default)]
-    pub struct NullAbilityMap;
+    /// Used for simple NPCs, which cast whatever is ready.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use bevy::utils::HashMap;
+    /// use leafwing_abilities::abilities::ability_mapping::{AbilityInputMap, PriorityAbilityMap};
+    /// use leafwing_input_manager::prelude::*;
+    ///
+    /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+    /// enum Action {
+    ///     Fireball,
+    /// }
+    ///
+    /// let meteor = Entity::from_raw(0);
+    /// let fireball = Entity::from_raw(1);
+    /// let mut map = PriorityAbilityMap::new(vec![meteor, fireball]);
+    ///
+    /// let mut usable = HashMap::default();
+    /// usable.insert(meteor, false);
+    /// usable.insert(fireball, true);
+    ///
+    /// let action_state = ActionState::<Action>::default();
+    /// assert_eq!(map.process_input(&action_state, usable), Some(fireball));
+    /// ```
+    #[derive(Default, Clone)]
+    pub struct PriorityAbilityMap {
+        priorities: Vec<Entity>,
+    }
 
-    impl AbilityInputMap for NullAbilityMap {
+    impl PriorityAbilityMap {
+        /// Abilities earlier in the list are preferred
+        pub fn new(priorities: Vec<Entity>) -> Self {
+            Self { priorities }
+        }
+    }
+
+    impl<A: Actionlike> AbilityInputMap<A> for PriorityAbilityMap {
         fn process_input(
-            &self,
-            _action_state: &ActionState,
-            _usable: HashMap<Entity, bool>,
+            &mut self,
+            _action_state: &ActionState<A>,
+            usable: HashMap<Entity, bool>,
         ) -> Option<Entity> {
-            None
+            self.priorities
+                .iter()
+                .copied()
+                .find(|ability_entity| usable.get(ability_entity) == Some(&true))
         }
 
         fn ability_list(&self) -> Vec<Entity> {
-            Vec::default()
+            self.priorities.clone()
         }
     }
 
-    /// Only one ability can be used at once,
-    /// and each ability corresponds to one input
-    #[derive(Default)]
-    pub struct SimpleAbilityMap {
-        map: HashMap<InputAction, Entity>,
+    /// Abilities are chosen without any input, at random from those which are usable
+    ///
+    /// Each ability is chosen in proportion to its weight.
+    /// As `process_input` takes `&mut self`, the RNG is simply stored in the map.
+    /// Maps created with the same seed make the same choices when given the same usable abilities,
+    /// making them suitable for replays and deterministic networking.
+    /// The RNG is `ChaCha8Rng`, whose output is the same on every platform,
+    /// but the same versions of `rand` and `rand_chacha` must be used everywhere.
+    /// Cloned maps continue from the same state of the RNG, and so make the same choices as the original.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use bevy::utils::HashMap;
+    /// use leafwing_abilities::abilities::ability_mapping::{AbilityInputMap, RandomAbilityMap};
+    /// use leafwing_input_manager::prelude::*;
+    ///
+    /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+    /// enum Action {
+    ///     Bite,
+    /// }
+    ///
+    /// let bite = Entity::from_raw(0);
+    /// let claw = Entity::from_raw(1);
+    /// let roar = Entity::from_raw(2);
+    /// let weights = vec![(bite, 3.0), (claw, 2.0), (roar, 1.0)];
+    ///
+    /// let mut usable = HashMap::default();
+    /// for ability in [bite, claw, roar] {
+    ///     usable.insert(ability, true);
+    /// }
+    ///
+    /// let action_state = ActionState::<Action>::default();
+    /// let choose_all = |mut map: RandomAbilityMap| -> Vec<Option<Entity>> {
+    ///     (0..20)
+    ///         .map(|_| map.process_input(&action_state, usable.clone()))
+    ///         .collect()
+    /// };
+    ///
+    /// // The same seed replays the same choices
+    /// let choices = choose_all(RandomAbilityMap::new(weights.clone(), 42));
+    /// assert_eq!(choices, choose_all(RandomAbilityMap::new(weights, 42)));
+    /// assert!(choices.iter().all(Option::is_some));
+    /// ```
+    #[derive(Clone)]
+    pub struct RandomAbilityMap {
+        weights: Vec<(Entity, f32)>,
+        rng: ChaCha8Rng,
+    }
+
+    impl RandomAbilityMap {
+        /// Abilities with weights of zero or less are never chosen
+        pub fn new(weights: Vec<(Entity, f32)>, seed: u64) -> Self {
+            Self {
+                weights,
+                rng: ChaCha8Rng::seed_from_u64(seed),
+            }
+        }
     }
 
-    impl AbilityInputMap for SimpleAbilityMap {
+    impl<A: Actionlike> AbilityInputMap<A> for RandomAbilityMap {
         fn process_input(
-            &self,
-            action_state: &ActionState,
+            &mut self,
+            _action_state: &ActionState<A>,
             usable: HashMap<Entity, bool>,
         ) -> Option<Entity> {
-            for action in InputAction::ABILITIES {
-                if action_state.just_pressed(action) {
-                    let ability_entity = *self.map.get(&action).unwrap();
-
-                    // Only attempt to use abilities if they can currently be used
-                    // If they can't, try another matching ability
-                    if *usable.get(&ability_entity).unwrap() {
-                        return Some(ability_entity);
-                    }
+            // Iterating over the weights, rather than the usable map, keeps the order deterministic
+            let eligible: Vec<(Entity, f32)> = self
+                .weights
+                .iter()
+                .copied()
+                .filter(|(ability_entity, weight)| {
+                    *weight > 0.0 && usable.get(ability_entity) == Some(&true)
+                })
+                .collect();
+
+            let total_weight: f32 = eligible.iter().map(|(_, weight)| weight).sum();
+            if total_weight <= 0.0 {
+                return None;
+            }
+
+            let mut roll = self.rng.gen_range(0.0..total_weight);
+            for &(ability_entity, weight) in eligible.iter() {
+                if roll < weight {
+                    return Some(ability_entity);
                 }
+                roll -= weight;
             }
-            None
+            // Guards against floating point error in the final subtraction
+            eligible.last().map(|&(ability_entity, _)| ability_entity)
         }
 
         fn ability_list(&self) -> Vec<Entity> {
-            self.map.values().cloned().collect()
+            self.weights
+                .iter()
+                .map(|&(ability_entity, _)| ability_entity)
+                .collect()
+        }
+    }
+
+    /// Component storing the `AbilityInputMap` used to choose the abilities of a unit
+    ///
+    /// Stored on the unit entity, alongside its `Abilities` and `ActionState<A>`.
+    #[derive(Component)]
+    pub struct AbilityMap<A: Actionlike> {
+        map: Box<dyn AbilityInputMap<A>>,
+        cancel_action: Option<A>,
+    }
+
+    impl<A: Actionlike> AbilityMap<A> {
+        pub fn new(map: impl AbilityInputMap<A>) -> Self {
+            Self {
+                map: Box::new(map),
+                cancel_action: None,
+            }
+        }
+
+        /// Pressing `cancel_action` stops any abilities of the unit that are still `Casting`
+        ///
+        /// Cancelled casts send an `AbilityCancelled` event, have paid no costs and do not start their cooldowns.
+        /// Cancelling is handled before any new ability is chosen, so the same input can also start an ability.
+        ///
+        /// ```rust
+        /// use bevy::app::{Events, ManualEventReader};
+        /// use bevy::prelude::*;
+        /// use leafwing_abilities::abilities::ability_mapping::{
+        ///     choose_ability_from_input, AbilityMap, NullAbilityMap,
+        /// };
+        /// use leafwing_abilities::abilities::{
+        ///     Abilities, AbilityCancelled, AbilityState, ActiveAbility,
+        /// };
+        /// use leafwing_input_manager::prelude::*;
+        ///
+        /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+        /// enum Action {
+        ///     Cancel,
+        /// }
+        ///
+        /// let mut world = World::new();
+        /// world.insert_resource(Time::default());
+        /// world.insert_resource(Events::<AbilityCancelled>::default());
+        ///
+        /// let pyroblast = world.spawn().id();
+        /// let mut abilities = Abilities::from_ability_list(vec![pyroblast]);
+        /// abilities.active_ability = ActiveAbility::new(pyroblast, AbilityState::Casting);
+        ///
+        /// let mut action_state = ActionState::<Action>::default();
+        /// action_state.press(Action::Cancel);
+        ///
+        /// let caster = world
+        ///     .spawn()
+        ///     .insert(abilities)
+        ///     .insert(AbilityMap::<Action>::new(NullAbilityMap).with_cancel_action(Action::Cancel))
+        ///     .insert(action_state)
+        ///     .id();
+        ///
+        /// SystemStage::single(choose_ability_from_input::<Action>).run(&mut world);
+        /// assert!(world.get::<Abilities>(caster).unwrap().active_ability() == ActiveAbility::NONE);
+        ///
+        /// let events = world.get_resource::<Events<AbilityCancelled>>().unwrap();
+        /// let mut reader = ManualEventReader::<AbilityCancelled>::default();
+        /// let cancelled: Vec<_> = reader.iter(events).copied().collect();
+        /// assert_eq!(
+        ///     cancelled,
+        ///     vec![AbilityCancelled {
+        ///         caster,
+        ///         ability: pyroblast,
+        ///         state: AbilityState::Casting
+        ///     }]
+        /// );
+        /// ```
+        pub fn with_cancel_action(mut self, cancel_action: A) -> Self {
+            self.cancel_action = Some(cancel_action);
+            self
+        }
+
+        pub fn cancel_action(&self) -> Option<A> {
+            self.cancel_action
+        }
+
+        pub fn ability_list(&self) -> Vec<Entity> {
+            self.map.ability_list()
+        }
+
+        pub(crate) fn process_input(
+            &mut self,
+            action_state: &ActionState<A>,
+            usable: HashMap<Entity, bool>,
+        ) -> Option<Entity> {
+            self.map.process_input(action_state, usable)
+        }
+
+        /// How charged the provided ability was when it was last chosen, from 0.0 to 1.0
+        pub fn charge(&self, ability: Entity) -> f32 {
+            self.map.charge(ability)
+        }
+
+        /// Each input and the ability it triggers, as described by the underlying `AbilityInputMap`
+        pub fn describe_bindings(&self) -> Vec<(A, Entity)> {
+            self.map.describe_bindings()
+        }
+    }
+
+    impl<A: Actionlike> Default for AbilityMap<A> {
+        fn default() -> Self {
+            Self::new(NullAbilityMap)
+        }
+    }
+
+    /// Clones the underlying `AbilityInputMap`, including any internal state such as sequence progress
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use bevy::utils::HashMap;
+    /// use leafwing_abilities::abilities::ability_mapping::{AbilityMap, SimpleAbilityMap};
+    /// use leafwing_abilities::abilities::Abilities;
+    /// use leafwing_input_manager::prelude::*;
+    ///
+    /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+    /// enum Action {
+    ///     Fireball,
+    ///     Frostbolt,
+    /// }
+    ///
+    /// let fireball = Entity::from_raw(0);
+    /// let frostbolt = Entity::from_raw(1);
+    /// let mut map = HashMap::default();
+    /// map.insert(Action::Fireball, fireball);
+    /// map.insert(Action::Frostbolt, frostbolt);
+    ///
+    /// let ability_map = AbilityMap::new(SimpleAbilityMap::new(map));
+    /// let abilities = Abilities::from_ability_map(&ability_map);
+    ///
+    /// // Spawn as many identical units as needed from the template
+    /// let cloned_map = ability_map.clone();
+    /// let cloned_abilities = abilities.clone();
+    ///
+    /// assert_eq!(cloned_abilities.ability_list(), abilities.ability_list());
+    /// assert_eq!(cloned_map.describe_bindings(), ability_map.describe_bindings());
+    /// assert_eq!(
+    ///     cloned_map.describe_bindings(),
+    ///     vec![(Action::Fireball, fireball), (Action::Frostbolt, frostbolt)]
+    /// );
+    /// ```
+    impl<A: Actionlike> Clone for AbilityMap<A> {
+        fn clone(&self) -> Self {
+            Self {
+                map: self.map.clone_box(),
+                cancel_action: self.cancel_action,
+            }
         }
     }
 
-    impl SimpleAbilityMap {
-        pub fn new(map: HashMap<InputAction, Entity>) -> Self {
-            Self { map }
+    /// Advances the internal state of every unit's `AbilityInputMap`
+    pub fn tick_ability_maps<A: Actionlike>(
+        mut query: Query<&mut AbilityMap<A>>,
+        time: Res<Time>,
+        timestep: Option<Res<AbilityTimestep>>,
+    ) {
+        let delta = ability_delta(&time, timestep);
+        for mut ability_map in query.iter_mut() {
+            ability_map.map.tick(delta);
         }
     }
 
+    /// Marker component for units whose abilities are chosen by the global `ActionState<A>` resource
+    ///
+    /// Add this to the player entity, alongside its `Abilities` and `AbilityMap<A>`.
+    /// Units with their own `ActionState<A>` component do not need it.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use bevy::utils::HashMap;
+    /// use leafwing_abilities::abilities::ability_mapping::{AbilityMap, InputControlled, SimpleAbilityMap};
+    /// use leafwing_abilities::abilities::{Abilities, Ability};
+    /// use leafwing_input_manager::prelude::*;
+    ///
+    /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+    /// enum Action {
+    ///     Fireball,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let fireball = world.spawn().insert(Ability).id();
+    ///
+    /// let mut map = HashMap::default();
+    /// map.insert(Action::Fireball, fireball);
+    /// let ability_map = AbilityMap::new(SimpleAbilityMap::new(map));
+    ///
+    /// world
+    ///     .spawn()
+    ///     .insert(Abilities::from_ability_map(&ability_map))
+    ///     .insert(ability_map)
+    ///     .insert(InputControlled);
+    /// ```
     #[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
-    struct InputControlled;
+    pub struct InputControlled;
 
-    pub fn choose_ability_from_input(
-        action_state: Res<ActionState>,
-        mut player_query: Query<&mut Abilities, With<InputControlled>>,
+    /// Chooses abilities for each unit with an `AbilityMap<A>`, based on its `ActionState<A>`
+    ///
+    /// Units with their own `ActionState<A>` component use it,
+    /// while other input-controlled units share the global `ActionState<A>` resource if it exists.
+    /// All remaining units, such as those controlled by a `PriorityAbilityMap`, see no inputs at all.
+    ///
+    /// Any number of units is supported, including none at all,
+    /// such as during cutscenes where the player has been despawned.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use bevy::app::Events;
+    /// use leafwing_abilities::abilities::ability_mapping::choose_ability_from_input;
+    /// use leafwing_abilities::abilities::AbilityCancelled;
+    /// use leafwing_input_manager::prelude::*;
+    ///
+    /// #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+    /// enum Action {
+    ///     Fireball,
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.insert_resource(Time::default());
+    /// world.insert_resource(Events::<AbilityCancelled>::default());
+    /// world.insert_resource(ActionState::<Action>::default());
+    ///
+    /// // No units with ability maps exist, so nothing happens
+    /// SystemStage::single(choose_ability_from_input::<Action>).run(&mut world);
+    /// ```
+    pub fn choose_ability_from_input<A: Actionlike>(
+        global_action_state: Option<Res<ActionState<A>>>,
+        mut cancelled_writer: EventWriter<AbilityCancelled>,
+        mut unit_query: Query<(
+            Entity,
+            Option<&ActionState<A>>,
+            Option<&InputControlled>,
+            &mut AbilityMap<A>,
+            &mut Abilities,
+            Option<&CurrentTarget>,
+            Option<&mut AbilityQueue>,
+        )>,
+        cast_time_query: Query<&CastTime, With<Ability>>,
+        toggleable_query: Query<Entity, (With<Ability>, With<Toggleable>)>,
+        category_query: Query<&AbilityCategory, With<Ability>>,
+        priority_query: Query<&AbilityPriority, With<Ability>>,
+        time: Res<Time>,
+        timestep: Option<Res<AbilityTimestep>>,
     ) {
-        let mut abilities = player_query.single_mut();
+        let delta = ability_delta(&time, timestep);
+        let no_input = ActionState::<A>::default();
+
+        for (
+            caster,
+            action_state,
+            input_controlled,
+            mut ability_map,
+            mut abilities,
+            current_target,
+            ability_queue,
+        ) in unit_query.iter_mut()
+        {
+            let action_state = match (action_state, input_controlled) {
+                (Some(action_state), _) => action_state,
+                (None, Some(_)) => match global_action_state.as_deref() {
+                    Some(action_state) => action_state,
+                    None => continue,
+                },
+                (None, None) => &no_input,
+            };
+
+            if let Some(cancel_action) = ability_map.cancel_action() {
+                if action_state.just_pressed(cancel_action) {
+                    for ability in abilities.cancel_casts() {
+                        cancelled_writer.send(AbilityCancelled {
+                            caster,
+                            ability,
+                            state: AbilityState::Casting,
+                        });
+                    }
+                }
+            }
+
+            // Toggled abilities can always be switched off
+            let mut usable = abilities.usable_map();
+            for ability_entity in abilities.toggled_on() {
+                usable.insert(ability_entity, true);
+            }
+
+            let chosen = match ability_map.process_input(action_state, usable) {
+                // Toggled abilities do not occupy the active ability
+                Some(ability_entity) if toggleable_query.get(ability_entity).is_ok() => {
+                    abilities.toggle(ability_entity);
+                    None
+                }
+                chosen => chosen,
+            };
 
-        // Only pick a new ability if none are active
-        if abilities.active_ability == ActiveAbility::NONE {
-            abilities.active_ability = ActiveAbility {
-                entity: abilities.process_input(&*action_state),
-                state: AbilityState::JustStarted,
+            let category_of = |ability_entity| {
+                category_query
+                    .get(ability_entity)
+                    .copied()
+                    .unwrap_or_default()
             };
+            let target = current_target.copied().unwrap_or_default().0;
+            let start = |abilities: &mut Abilities, ability_entity| {
+                let state = initial_state(cast_time_query.get(ability_entity).ok());
+                *abilities.slot_mut(category_of(ability_entity)) =
+                    ActiveAbility::new(ability_entity, state)
+                        .with_target(target)
+                        .with_charge(ability_map.charge(ability_entity));
+            };
+
+            // Units with a queue use it in place of their input buffer
+            if let Some(mut ability_queue) = ability_queue {
+                if let Some(ability_entity) = chosen {
+                    let priority = priority_query
+                        .get(ability_entity)
+                        .copied()
+                        .unwrap_or_default();
+                    ability_queue.push(ability_entity, priority);
+                }
+
+                // Entries whose category is occupied wait, without blocking those of free categories
+                let highest = ability_queue.highest(|ability_entity| {
+                    abilities.is_usable(ability_entity) == Some(true)
+                        && abilities.slot(category_of(ability_entity)) == ActiveAbility::NONE
+                });
+                if let Some(ability_entity) = highest {
+                    let category = category_of(ability_entity);
+                    start(&mut abilities, ability_entity);
+                    ability_queue.discard(|queued_entity| category_of(queued_entity) == category);
+                }
+
+                ability_queue.tick(delta);
+                continue;
+            }
+
+            // Only start an ability if no other ability of its category is active,
+            // including those still being cast
+            if let Some(ability_entity) = chosen {
+                if abilities.slot(category_of(ability_entity)) == ActiveAbility::NONE {
+                    start(&mut abilities, ability_entity);
+                    // Fresh inputs take priority over buffered ones, which are discarded
+                    abilities.clear_input_buffer();
+                } else {
+                    abilities.buffer_input(ability_entity);
+                }
+            }
+
+            // Buffered abilities are started as soon as their category is free
+            if let Some(ability_entity) = abilities.buffered_input() {
+                if abilities.slot(category_of(ability_entity)) == ActiveAbility::NONE {
+                    if let Some(ability_entity) = abilities.take_buffered_input() {
+                        start(&mut abilities, ability_entity);
+                    }
+                }
+            }
+
+            abilities.tick_input_buffer(delta);
         }
     }
 }
 
-pub mod cooldowns {
-    use bevy::prelude::*;
-    use core::time::Duration;
+/// Usage counters for live-ops and balance analysis
+///
+/// Only available with the `telemetry` feature, so that shipping builds can exclude it.
+#[cfg(feature = "telemetry")]
+pub mod telemetry {
+    use super::*;
 
-    use super::usability::Usable;
-    use super::Ability;
+    /// How often this ability has been used, and how much has been spent on it
+    ///
+    /// Add this to the ability entities you wish to track.
+    /// Spending is counted by `spend_resource`, summed over every resource type as `f64`.
+    ///
+    /// ```rust
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::telemetry::{record_activations, AbilityStats};
+    /// use leafwing_abilities::abilities::{Abilities, Ability, AbilityState, ActiveAbility};
+    ///
+    /// let mut world = World::new();
+    /// let fireball = world.spawn().insert(Ability).insert(AbilityStats::default()).id();
+    ///
+    /// let mut abilities = Abilities::from_ability_list(vec![fireball]);
+    /// abilities.active_ability = ActiveAbility::new(fireball, AbilityState::JustStarted);
+    /// world.spawn().insert(abilities);
+    ///
+    /// let mut stage = SystemStage::single(record_activations);
+    /// stage.run(&mut world);
+    /// stage.run(&mut world);
+    ///
+    /// let mut stats = world.get_mut::<AbilityStats>(fireball).unwrap();
+    /// assert_eq!(stats.activations(), 2);
+    ///
+    /// let previous = stats.reset();
+    /// assert_eq!(previous.activations(), 2);
+    /// assert_eq!(stats.activations(), 0);
+    /// ```
+    #[derive(Component, Clone, Copy, PartialEq, Debug, Default)]
+    pub struct AbilityStats {
+        activations: u64,
+        total_resource_spent: f64,
+    }
 
-    #[derive(Component, Clone)]
-    pub struct Cooldown {
-        timer: Timer,
-        charges: u8,
-        max_charges: u8,
+    impl AbilityStats {
+        /// The number of times this ability has taken effect
+        pub fn activations(&self) -> u64 {
+            self.activations
+        }
+
+        /// The total amount of resources spent on this ability, across every resource type
+        pub fn total_resource_spent(&self) -> f64 {
+            self.total_resource_spent
+        }
+
+        /// Clears the counters, returning their previous values
+        pub fn reset(&mut self) -> Self {
+            core::mem::take(self)
+        }
+
+        pub(crate) fn record_spend(&mut self, amount: f32) {
+            self.total_resource_spent += amount as f64;
+        }
     }
 
-    impl Cooldown {
-        pub fn new(seconds: f32) -> Self {
-            let mut timer = Timer::from_seconds(seconds, false);
-            // All abilities should be available for use on new entities
-            timer.tick(Duration::from_secs_f32(seconds));
+    /// Counts each activation of the abilities with `AbilityStats`
+    pub fn record_activations(
+        unit_query: Query<&Abilities>,
+        mut stats_query: Query<&mut AbilityStats, With<Ability>>,
+    ) {
+        for abilities in unit_query.iter() {
+            for active_ability in abilities.active_abilities() {
+                if active_ability.state != AbilityState::JustStarted {
+                    continue;
+                }
 
-            Self {
-                timer,
-                charges: 1,
-                max_charges: 1,
+                if let Some(ability_entity) = active_ability.entity {
+                    if let Ok(mut stats) = stats_query.get_mut(ability_entity) {
+                        stats.activations += 1;
+                    }
+                }
             }
         }
+    }
+}
 
-        pub fn new_with_charges(seconds: f32, max_charges: u8) {}
+/// Concise construction of ability entities
+pub mod spawning {
+    use super::cooldowns::Cooldown;
+    use super::usability::{UsabilityReason, Usable};
+    use super::{Ability, AbilityCategory};
+    use crate::resources::{AbilityCost, ResourceType};
+    use bevy::ecs::system::EntityCommands;
+    use bevy::prelude::*;
 
-        pub fn tick(&mut self, delta: Duration) {
-            self.timer.tick(delta);
+    /// Spawns ability entities through `Commands`
+    ///
+    /// ```rust
+    /// use bevy::ecs::system::CommandQueue;
+    /// use bevy::prelude::*;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    /// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+    /// use leafwing_abilities::abilities::{Abilities, Ability};
+    ///
+    /// let mut world = World::new();
+    /// let mut command_queue = CommandQueue::default();
+    /// let mut commands = Commands::new(&mut command_queue, &world);
+    ///
+    /// let dash = commands
+    ///     .spawn_ability()
+    ///     .with_cooldown(Cooldown::new(4.0))
+    ///     .id();
+    /// commands
+    ///     .spawn()
+    ///     .insert(Abilities::from_ability_list(vec![dash]));
+    /// command_queue.apply(&mut world);
+    ///
+    /// assert!(world.get::<Ability>(dash).is_some());
+    /// assert!(world.get::<Cooldown>(dash).is_some());
+    /// ```
+    pub trait SpawnAbilityExt<'w, 's> {
+        /// Spawns a new entity with the `Ability` marker, returning a builder for its other components
+        fn spawn_ability<'a>(&'a mut self) -> AbilityBuilder<'w, 's, 'a>;
+    }
+
+    impl<'w, 's> SpawnAbilityExt<'w, 's> for Commands<'w, 's> {
+        fn spawn_ability<'a>(&'a mut self) -> AbilityBuilder<'w, 's, 'a> {
+            let mut entity_commands = self.spawn();
+            entity_commands
+                .insert(Ability)
+                .insert(Usable(UsabilityReason::Unchecked));
+            AbilityBuilder { entity_commands }
         }
+    }
+
+    /// Adds components to a freshly spawned ability entity
+    ///
+    /// Created by `SpawnAbilityExt::spawn_ability`.
+    pub struct AbilityBuilder<'w, 's, 'a> {
+        entity_commands: EntityCommands<'w, 's, 'a>,
+    }
 
-        pub fn start(&mut self) {
-            self.timer.reset()
+    impl<'w, 's, 'a> AbilityBuilder<'w, 's, 'a> {
+        pub fn with_cooldown(mut self, cooldown: Cooldown) -> Self {
+            self.entity_commands.insert(cooldown);
+            self
         }
 
-        pub fn remaining(&self) -> f32 {
-            self.timer.percent_left()
+        pub fn with_cost<R: ResourceType>(mut self, cost: R) -> Self {
+            self.entity_commands.insert(AbilityCost(cost));
+            self
         }
 
-        pub fn finished(&self) -> bool {
-            self.timer.finished()
+        pub fn with_category(mut self, category: AbilityCategory) -> Self {
+            self.entity_commands.insert(category);
+            self
         }
-    }
 
-    pub(crate) fn tick_cooldowns(mut query: Query<&mut Cooldown>, time: Res<Time>) {
-        for mut cooldown in query.iter_mut() {
-            // Extra check here avoids change-detection false positives
-            if !cooldown.finished() {
-                cooldown.tick(time.delta());
-            }
+        /// Inserts any other component, such as the unique marker component of this ability
+        pub fn with<C: Component>(mut self, component: C) -> Self {
+            self.entity_commands.insert(component);
+            self
         }
-    }
 
-    pub(crate) fn check_cooldowns(
-        mut query: Query<(&Cooldown, &mut Usable), (With<Ability>, Changed<Cooldown>)>,
-    ) {
-        for (cooldown, mut usable) in query.iter_mut() {
-            if !cooldown.finished() {
-                *usable = Usable(false);
-            }
+        /// The entity of the ability, to be passed to `Abilities` and `AbilityInputMap`s
+        pub fn id(&self) -> Entity {
+            self.entity_commands.id()
         }
     }
 }
+
+pub mod cooldowns;
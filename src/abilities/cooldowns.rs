@@ -0,0 +1,1531 @@
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use core::any::Any;
+use core::time::Duration;
+
+use super::usability::{UsabilityReason, Usable};
+use super::{
+    ability_delta, Abilities, Ability, AbilityCancelled, AbilityState, AbilityTimestep,
+    LocalTimeScale,
+};
+
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "serialization::CooldownData",
+        into = "serialization::CooldownData"
+    )
+)]
+pub struct Cooldown {
+    timer: Timer,
+    /// The time taken to restore each charge, unless started with `Cooldown::start_with`
+    duration: Duration,
+    charges: u8,
+    max_charges: u8,
+    rate: f32,
+    /// Used to send `CooldownFinished` events only once per completion
+    was_finished: bool,
+}
+
+impl Cooldown {
+    pub fn new(seconds: f32) -> Self {
+        Self::new_with_charges(seconds, 1)
+    }
+
+    /// Creates a cooldown that can store up to `max_charges` uses
+    ///
+    /// The timer repeats: each time it completes, one charge is restored.
+    /// Cooldowns without any charges could never be used, so a `max_charges` of 0 is treated as 1.
+    pub fn new_with_charges(seconds: f32, max_charges: u8) -> Self {
+        Self::builder()
+            .seconds(seconds)
+            .charges(max_charges)
+            .build()
+    }
+
+    /// Creates a cooldown which must fully elapse before the ability can first be used,
+    /// such as for ultimate abilities
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut ultimate = Cooldown::new_unavailable(90.0);
+    /// assert!(!ultimate.ready());
+    ///
+    /// ultimate.tick(Duration::from_secs(90));
+    /// assert!(ultimate.ready());
+    /// ```
+    pub fn new_unavailable(seconds: f32) -> Self {
+        Self::builder().seconds(seconds).starts_ready(false).build()
+    }
+
+    /// Configures a new cooldown, one option at a time
+    pub fn builder() -> CooldownBuilder {
+        CooldownBuilder::default()
+    }
+
+    /// Advances the cooldown, restoring one charge each time the timer completes
+    ///
+    /// Fully charged cooldowns do not tick.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut blink = Cooldown::new_with_charges(2.0, 3);
+    /// for _ in 0..3 {
+    ///     blink.start();
+    /// }
+    /// assert_eq!(blink.charges(), 0);
+    ///
+    /// blink.tick(Duration::from_secs(5));
+    /// assert_eq!(blink.charges(), 2);
+    /// ```
+    ///
+    /// Cooldowns of zero seconds restore all of their charges on the next tick:
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut punch = Cooldown::default();
+    /// punch.start();
+    /// assert!(punch.ready());
+    ///
+    /// punch.tick(Duration::from_millis(16));
+    /// assert_eq!(punch.charges(), 1);
+    /// assert!(punch.finished());
+    /// ```
+    pub fn tick(&mut self, delta: Duration) {
+        if self.charges >= self.max_charges || self.paused() {
+            return;
+        }
+
+        // Repeating timers cannot be ticked with a duration of zero
+        if self.is_instant() {
+            self.charges = self.max_charges;
+            return;
+        }
+
+        self.timer.tick(delta);
+        let times_finished = self.timer.times_finished();
+        // Charges started with a custom duration only affect the charge they restore
+        if times_finished > 0 {
+            self.timer.set_duration(self.duration);
+        }
+        let restored = self.charges as u32 + times_finished;
+        self.charges = restored.min(self.max_charges as u32) as u8;
+    }
+
+    /// Consumes a single charge
+    ///
+    /// The timer is only reset if the cooldown was fully charged:
+    /// otherwise it is already counting down towards the next charge.
+    pub fn start(&mut self) {
+        if self.charges == self.max_charges {
+            self.timer.reset();
+        }
+        self.charges = self.charges.saturating_sub(1);
+    }
+
+    /// Consumes a single charge, taking `duration` rather than the usual duration to restore it
+    ///
+    /// Like `Cooldown::start`, this only affects the timer if the cooldown was fully charged.
+    /// Later charges are restored at the usual duration.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut charged_shot = Cooldown::new(10.0);
+    /// charged_shot.start_with(Duration::from_secs(4));
+    /// assert_eq!(charged_shot.remaining_seconds(), 4.0);
+    ///
+    /// charged_shot.tick(Duration::from_secs(4));
+    /// assert!(charged_shot.ready());
+    ///
+    /// charged_shot.start();
+    /// assert_eq!(charged_shot.remaining_seconds(), 10.0);
+    /// ```
+    pub fn start_with(&mut self, duration: Duration) {
+        if self.charges == self.max_charges {
+            self.timer.set_duration(duration);
+            self.timer.reset();
+        }
+        self.charges = self.charges.saturating_sub(1);
+    }
+
+    /// The time taken to restore each charge, unless started with `Cooldown::start_with`
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn remaining(&self) -> f32 {
+        if self.charges == self.max_charges || self.is_instant() {
+            0.0
+        } else {
+            self.timer.percent_left()
+        }
+    }
+
+    /// Immediately finishes the cooldown, restoring all of its charges
+    ///
+    /// ```rust
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut dash = Cooldown::new_with_charges(3.0, 2);
+    /// dash.start();
+    /// dash.start();
+    /// assert!(!dash.finished());
+    ///
+    /// dash.refresh();
+    /// assert!(dash.finished());
+    /// assert_eq!(dash.charges(), 2);
+    /// ```
+    pub fn refresh(&mut self) {
+        if self.charges == self.max_charges {
+            return;
+        }
+
+        if !self.is_instant() {
+            let remaining = self.timer.duration().saturating_sub(self.timer.elapsed());
+            self.timer.tick(remaining);
+        }
+        self.charges = self.max_charges;
+    }
+
+    /// Shortens the time until the next charge is restored
+    ///
+    /// Reductions which exceed the remaining time restore that charge,
+    /// and any excess carries over to the charges after it, so that resets are never wasted.
+    /// Reductions are not affected by the `rate` of the cooldown.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut blink = Cooldown::new_with_charges(2.0, 3);
+    /// blink.start();
+    /// blink.start();
+    /// assert_eq!(blink.charges(), 1);
+    ///
+    /// // One charge is restored, and the excess banks half of the next one
+    /// blink.reduce(Duration::from_secs(3));
+    /// assert_eq!(blink.charges(), 2);
+    /// assert_eq!(blink.charge_progress(), 2.5);
+    ///
+    /// // Once fully charged, any remaining excess is lost
+    /// blink.reduce(Duration::from_secs(10));
+    /// assert_eq!(blink.charges(), 3);
+    /// assert!(blink.finished());
+    /// ```
+    pub fn reduce(&mut self, amount: Duration) {
+        self.tick(amount);
+    }
+
+    /// The number of seconds until the next charge is available
+    pub fn remaining_seconds(&self) -> f32 {
+        self.timer.duration().as_secs_f32() - self.elapsed_seconds()
+    }
+
+    /// The number of seconds spent recharging the next charge
+    pub fn elapsed_seconds(&self) -> f32 {
+        if self.charges == self.max_charges {
+            self.timer.duration().as_secs_f32()
+        } else {
+            self.timer.elapsed_secs()
+        }
+    }
+
+    /// The multiplier applied to elapsed time when ticking this cooldown
+    pub fn rate(&self) -> f32 {
+        self.rate
+    }
+
+    /// Sets the rate at which this cooldown recovers
+    ///
+    /// A rate of 2.0 halves the cooldown, while a rate of 0.0 freezes it entirely.
+    /// Negative rates are clamped to zero.
+    pub fn set_rate(&mut self, rate: f32) {
+        self.rate = rate.max(0.0);
+    }
+
+    /// The number of charges available, plus the progress towards the next charge
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut dash = Cooldown::new_with_charges(2.0, 3);
+    /// for _ in 0..3 {
+    ///     dash.start();
+    /// }
+    ///
+    /// dash.tick(Duration::from_secs(3));
+    /// assert_eq!(dash.charge_progress(), 1.5);
+    /// ```
+    pub fn charge_progress(&self) -> f32 {
+        if self.charges >= self.max_charges || self.is_instant() {
+            return self.max_charges as f32;
+        }
+
+        let progress = self.charges as f32 + (1.0 - self.timer.percent_left());
+        progress.min(self.max_charges as f32)
+    }
+
+    pub fn charges(&self) -> u8 {
+        self.charges
+    }
+
+    pub fn max_charges(&self) -> u8 {
+        self.max_charges
+    }
+
+    /// Stops the cooldown from recovering until it is resumed
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut fireball = Cooldown::new(3.0);
+    /// fireball.start();
+    /// fireball.pause();
+    ///
+    /// fireball.tick(Duration::from_secs(2));
+    /// assert_eq!(fireball.remaining_seconds(), 3.0);
+    ///
+    /// fireball.resume();
+    /// fireball.tick(Duration::from_secs(2));
+    /// assert_eq!(fireball.remaining_seconds(), 1.0);
+    /// ```
+    pub fn pause(&mut self) {
+        self.timer.pause();
+    }
+
+    pub fn resume(&mut self) {
+        self.timer.unpause();
+    }
+
+    pub fn paused(&self) -> bool {
+        self.timer.paused()
+    }
+
+    /// Has the timer towards the next charge completed?
+    ///
+    /// Cooldowns with several charges may still be used while their timer is running:
+    /// use `Cooldown::ready` to check whether the ability can be used.
+    pub fn finished(&self) -> bool {
+        self.is_instant() || self.timer.finished()
+    }
+
+    /// Can the ability be used right now, as far as this cooldown is concerned?
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::abilities::cooldowns::Cooldown;
+    ///
+    /// let mut dash = Cooldown::new_with_charges(4.0, 2);
+    /// dash.start();
+    /// dash.tick(Duration::from_secs(1));
+    ///
+    /// // The next charge is still being restored, but one is banked
+    /// assert!(!dash.finished());
+    /// assert!(dash.ready());
+    ///
+    /// dash.start();
+    /// assert!(!dash.ready());
+    /// ```
+    pub fn ready(&self) -> bool {
+        self.charges > 0 || self.finished()
+    }
+
+    /// Is the charge being restored zero seconds long, and so always finished?
+    fn is_instant(&self) -> bool {
+        self.timer.duration().is_zero()
+    }
+}
+
+/// A cooldown of zero seconds, which is always ready
+impl Default for Cooldown {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+/// `Timer` cannot be serialized directly, so cooldowns are stored as their durations instead
+#[cfg(feature = "serde")]
+mod serialization {
+    use super::Cooldown;
+    use bevy::core::Timer;
+    use core::time::Duration;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub(super) struct CooldownData {
+        duration: Duration,
+        /// The duration of the charge currently being restored, if it was started with `Cooldown::start_with`
+        #[serde(default)]
+        current_duration: Option<Duration>,
+        elapsed: Duration,
+        charges: u8,
+        max_charges: u8,
+        rate: f32,
+        paused: bool,
+    }
+
+    impl From<Cooldown> for CooldownData {
+        fn from(cooldown: Cooldown) -> Self {
+            Self {
+                duration: cooldown.duration,
+                current_duration: Some(cooldown.timer.duration())
+                    .filter(|&current_duration| current_duration != cooldown.duration),
+                elapsed: cooldown.timer.elapsed(),
+                charges: cooldown.charges,
+                max_charges: cooldown.max_charges,
+                rate: cooldown.rate,
+                paused: cooldown.paused(),
+            }
+        }
+    }
+
+    impl From<CooldownData> for Cooldown {
+        fn from(data: CooldownData) -> Self {
+            // Cooldowns without any charges could never be used
+            let max_charges = data.max_charges.max(1);
+            let charges = data.charges.min(max_charges);
+            let mut timer = Timer::new(data.current_duration.unwrap_or(data.duration), true);
+            if charges == max_charges {
+                // Fully charged cooldowns are always finished,
+                // but repeating timers cannot be ticked with a duration of zero
+                if !timer.duration().is_zero() {
+                    timer.tick(timer.duration());
+                }
+            } else {
+                timer.set_elapsed(data.elapsed);
+            }
+            if data.paused {
+                timer.pause();
+            }
+
+            let mut cooldown = Self {
+                was_finished: false,
+                timer,
+                duration: data.duration,
+                charges,
+                max_charges,
+                rate: data.rate.max(0.0),
+            };
+            cooldown.was_finished = cooldown.finished();
+            cooldown
+        }
+    }
+}
+
+/// Builds a `Cooldown`
+///
+/// By default, cooldowns have a single charge, recover at a rate of 1.0 and start ready for use.
+///
+/// ```rust
+/// use leafwing_abilities::abilities::cooldowns::Cooldown;
+///
+/// // Abilities gained mid-fight should not be available immediately
+/// let cooldown = Cooldown::builder()
+///     .seconds(8.0)
+///     .charges(2)
+///     .starts_ready(false)
+///     .build();
+///
+/// assert!(!cooldown.finished());
+/// assert_eq!(cooldown.charges(), 0);
+/// assert_eq!(cooldown.max_charges(), 2);
+/// assert_eq!(cooldown.remaining_seconds(), 8.0);
+///
+/// // Every cooldown has at least one charge
+/// let cooldown = Cooldown::builder().seconds(8.0).charges(0).build();
+/// assert_eq!(cooldown.max_charges(), 1);
+/// assert!(cooldown.ready());
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CooldownBuilder {
+    seconds: f32,
+    charges: u8,
+    starts_ready: bool,
+    rate: f32,
+}
+
+impl Default for CooldownBuilder {
+    fn default() -> Self {
+        Self {
+            seconds: 0.0,
+            charges: 1,
+            starts_ready: true,
+            rate: 1.0,
+        }
+    }
+}
+
+impl CooldownBuilder {
+    /// The time taken to restore each charge
+    pub fn seconds(mut self, seconds: f32) -> Self {
+        self.seconds = seconds;
+        self
+    }
+
+    /// The maximum number of charges that can be stored
+    ///
+    /// Cooldowns need at least one charge to be used, so 0 is treated as 1.
+    pub fn charges(mut self, charges: u8) -> Self {
+        self.charges = charges;
+        self
+    }
+
+    /// Should the cooldown begin fully charged, or with no charges at all?
+    pub fn starts_ready(mut self, starts_ready: bool) -> Self {
+        self.starts_ready = starts_ready;
+        self
+    }
+
+    /// The multiplier applied to elapsed time when ticking the cooldown
+    pub fn rate(mut self, rate: f32) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub fn build(self) -> Cooldown {
+        // Cooldowns without any charges could never be used
+        let max_charges = self.charges.max(1);
+        let mut timer = Timer::from_seconds(self.seconds, true);
+        // Repeating timers cannot be ticked with a duration of zero
+        if self.starts_ready && !timer.duration().is_zero() {
+            timer.tick(timer.duration());
+        }
+
+        let mut cooldown = Cooldown {
+            was_finished: false,
+            duration: timer.duration(),
+            timer,
+            charges: if self.starts_ready { max_charges } else { 0 },
+            max_charges,
+            rate: 1.0,
+        };
+        cooldown.was_finished = cooldown.finished();
+        cooldown.set_rate(self.rate);
+        cooldown
+    }
+}
+
+/// While this resource is set to `true`, no cooldowns recover, such as during cutscenes and pause menus
+///
+/// Individual cooldowns can be paused using `Cooldown::pause`, or gated with `CooldownFrozen`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct GloballyPaused(pub bool);
+
+/// Marker component for abilities whose cooldowns do not progress, such as while their caster is seen
+///
+/// Insert and remove this from your own systems to gate when the cooldown recharges.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy::utils::Instant;
+/// use core::time::Duration;
+/// use leafwing_abilities::abilities::cooldowns::{tick_cooldowns, Cooldown, CooldownFrozen};
+///
+/// let mut world = World::new();
+/// let start = Instant::now();
+/// let mut time = Time::default();
+/// time.update_with_instant(start);
+/// world.insert_resource(time);
+///
+/// let mut cooldown = Cooldown::new(10.0);
+/// cooldown.start();
+/// let stealth = world.spawn().insert(cooldown).insert(CooldownFrozen).id();
+///
+/// let mut stage = SystemStage::single(tick_cooldowns);
+/// for frame in 1..=3 {
+///     let mut time = world.get_resource_mut::<Time>().unwrap();
+///     time.update_with_instant(start + Duration::from_secs(frame));
+///     stage.run(&mut world);
+/// }
+/// assert_eq!(world.get::<Cooldown>(stealth).unwrap().remaining_seconds(), 10.0);
+///
+/// world.entity_mut(stealth).remove::<CooldownFrozen>();
+/// let mut time = world.get_resource_mut::<Time>().unwrap();
+/// time.update_with_instant(start + Duration::from_secs(4));
+/// stage.run(&mut world);
+/// assert_eq!(world.get::<Cooldown>(stealth).unwrap().remaining_seconds(), 9.0);
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct CooldownFrozen;
+
+/// Advances the `Cooldown` of every ability which is not `CooldownFrozen`, scaled by any `LocalTimeScale`
+pub fn tick_cooldowns(
+    mut query: Query<(&mut Cooldown, Option<&LocalTimeScale>), Without<CooldownFrozen>>,
+    time: Res<Time>,
+    timestep: Option<Res<AbilityTimestep>>,
+    globally_paused: Option<Res<GloballyPaused>>,
+) {
+    if let Some(globally_paused) = globally_paused {
+        if globally_paused.0 {
+            return;
+        }
+    }
+
+    let delta = ability_delta(&time, timestep);
+    for (mut cooldown, local_time_scale) in query.iter_mut() {
+        // Extra check here avoids change-detection false positives
+        if cooldown.charges() < cooldown.max_charges() {
+            let rate = cooldown.rate();
+            let delta = LocalTimeScale::scale(local_time_scale, delta);
+            cooldown.tick(delta.mul_f32(rate));
+        }
+    }
+}
+
+pub(crate) fn check_cooldowns(mut query: Query<(&Cooldown, &mut Usable), With<Ability>>) {
+    for (cooldown, mut usable) in query.iter_mut() {
+        // Abilities with charges remaining can be used while the next charge is restored
+        if cooldown.charges() == 0 {
+            usable.block(UsabilityReason::OnCooldown);
+        }
+    }
+}
+
+/// The `GateCheck` matching `check_cooldowns` and `check_shared_charges`
+pub fn cooldown_gate(world: &World, _caster: Entity, ability: Entity) -> Option<UsabilityReason> {
+    let out_of_charges = |cooldown_entity| {
+        world
+            .get::<Cooldown>(cooldown_entity)
+            .map_or(false, |cooldown| cooldown.charges() == 0)
+    };
+    let shared_out_of_charges = world
+        .get::<SharedCharges>(ability)
+        .map_or(false, |&SharedCharges(pool)| out_of_charges(pool));
+
+    if out_of_charges(ability) || shared_out_of_charges {
+        Some(UsabilityReason::OnCooldown)
+    } else {
+        None
+    }
+}
+
+/// Mirrors the fraction of the cooldown of an ability that remains, for binding to UI or shaders
+///
+/// Add this to ability entities alongside their `Cooldown`.
+/// For cooldowns with several charges, this is the progress towards the next charge.
+#[derive(Component, Clone, Copy, PartialEq, Debug, Default)]
+pub struct CooldownDisplay(pub f32);
+
+pub(crate) fn update_cooldown_displays(mut query: Query<(&Cooldown, &mut CooldownDisplay)>) {
+    for (cooldown, mut display) in query.iter_mut() {
+        let remaining = cooldown.remaining();
+        // Extra check here avoids change-detection false positives
+        if display.0 != remaining {
+            display.0 = remaining;
+        }
+    }
+}
+
+/// Sent whenever the cooldown of an ability completes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CooldownFinished {
+    pub ability: Entity,
+}
+
+/// Sent whenever the cooldown of an ability is started, such as to play a sound
+///
+/// Sent for every cooldown started by this crate, and for each `StartCooldown` event.
+/// For `SharedCharges`, `ability` is the entity of the shared pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CooldownStarted {
+    pub ability: Entity,
+}
+
+/// Send this event to start the cooldown of an `ability` from your own systems,
+/// rather than calling `Cooldown::start` directly, so that `CooldownStarted` is sent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StartCooldown {
+    pub ability: Entity,
+}
+
+/// Applies `StartCooldown` events to the targeted cooldowns
+pub(crate) fn start_cooldowns(
+    mut event_reader: EventReader<StartCooldown>,
+    mut event_writer: EventWriter<CooldownStarted>,
+    mut query: Query<&mut Cooldown>,
+) {
+    for &StartCooldown { ability } in event_reader.iter() {
+        if let Ok(mut cooldown) = query.get_mut(ability) {
+            cooldown.start();
+            event_writer.send(CooldownStarted { ability });
+        }
+    }
+}
+
+/// Send this event to shorten the cooldown of an `ability`, such as when its user lands a takedown
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReduceCooldown {
+    pub ability: Entity,
+    pub amount: CooldownReduction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CooldownReduction {
+    /// A fixed amount of time
+    Flat(Duration),
+    /// A fraction of the full duration of the cooldown
+    Fraction(f32),
+}
+
+/// Applies `ReduceCooldown` events to the targeted cooldowns
+pub(crate) fn reduce_cooldowns(
+    mut event_reader: EventReader<ReduceCooldown>,
+    mut query: Query<&mut Cooldown, With<Ability>>,
+) {
+    for &ReduceCooldown { ability, amount } in event_reader.iter() {
+        if let Ok(mut cooldown) = query.get_mut(ability) {
+            let amount = match amount {
+                CooldownReduction::Flat(duration) => duration,
+                CooldownReduction::Fraction(fraction) => {
+                    cooldown.timer.duration().mul_f32(fraction.max(0.0))
+                }
+            };
+            cooldown.reduce(amount);
+        }
+    }
+}
+
+/// Shortens the cooldown of an ability once it has started, then removes itself
+///
+/// Inserted by `spend_resource` for abilities with an `OverclockCost`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverclockReduction(pub Duration);
+
+/// Applies each `OverclockReduction` to the cooldown of its ability
+///
+/// Runs in `AbilityStages::post`, after the cooldown has been started
+/// by `CooldownTrigger::OnStart` or by your own systems.
+pub(crate) fn apply_overclock_reductions(
+    mut commands: Commands,
+    mut query: Query<(Entity, &OverclockReduction, &mut Cooldown), With<Ability>>,
+) {
+    for (ability_entity, &OverclockReduction(reduction), mut cooldown) in query.iter_mut() {
+        cooldown.reduce(reduction);
+        commands
+            .entity(ability_entity)
+            .remove::<OverclockReduction>();
+    }
+}
+
+/// Sends a `CooldownFinished` event on the frame that each cooldown finishes
+///
+/// ```rust
+/// use bevy::app::Events;
+/// use bevy::prelude::*;
+/// use core::time::Duration;
+/// use leafwing_abilities::abilities::cooldowns::{
+///     send_cooldown_finished_events, Cooldown, CooldownFinished,
+/// };
+/// use leafwing_abilities::abilities::Ability;
+///
+/// let mut world = World::new();
+/// world.insert_resource(Events::<CooldownFinished>::default());
+/// let fireball = world.spawn().insert(Ability).insert(Cooldown::new(1.0)).id();
+/// world.get_mut::<Cooldown>(fireball).unwrap().start();
+///
+/// let mut stage = SystemStage::single(send_cooldown_finished_events);
+/// let mut reader = world
+///     .get_resource::<Events<CooldownFinished>>()
+///     .unwrap()
+///     .get_reader();
+/// let mut frame = |world: &mut World, delta: Duration| {
+///     world.get_mut::<Cooldown>(fireball).unwrap().tick(delta);
+///     stage.run(world);
+///     let events = world.get_resource::<Events<CooldownFinished>>().unwrap();
+///     reader.iter(events).count()
+/// };
+///
+/// assert_eq!(frame(&mut world, Duration::from_millis(500)), 0);
+/// // Ticking past the end of the cooldown finishes it exactly once
+/// assert_eq!(frame(&mut world, Duration::from_millis(800)), 1);
+/// assert_eq!(frame(&mut world, Duration::from_millis(500)), 0);
+/// assert_eq!(frame(&mut world, Duration::from_millis(500)), 0);
+/// ```
+pub fn send_cooldown_finished_events(
+    mut query: Query<(Entity, &mut Cooldown), With<Ability>>,
+    mut event_writer: EventWriter<CooldownFinished>,
+) {
+    for (ability, mut cooldown) in query.iter_mut() {
+        let finished = cooldown.finished();
+        // Extra check here avoids change-detection false positives
+        if finished != cooldown.was_finished {
+            if finished {
+                event_writer.send(CooldownFinished { ability });
+            }
+            cooldown.was_finished = finished;
+        }
+    }
+}
+
+/// Other abilities whose cooldowns are started whenever this ability is used
+///
+/// Links are one-way and are not followed transitively:
+/// if A links B and B links C, using A does not start the cooldown of C.
+/// Abilities may link to each other, to share a cooldown.
+#[derive(Component, Clone, PartialEq, Debug, Default)]
+pub struct LinkedCooldowns(pub Vec<Entity>);
+
+/// Starts the cooldowns linked to each ability that was just used
+///
+/// Each linked cooldown is started at most once per frame,
+/// even if several abilities that link to it were used at once.
+/// Abilities used this frame with `CooldownTrigger::OnStart` are never started by their links,
+/// as their own trigger already starts them,
+/// so abilities that link to each other only spend a single charge each.
+///
+/// ```rust
+/// use bevy::app::{Events, ManualEventReader};
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::cooldowns::{
+///     start_linked_cooldowns, Cooldown, CooldownStarted, CooldownTrigger, LinkedCooldowns,
+/// };
+/// use leafwing_abilities::abilities::{
+///     Abilities, Ability, AbilityCategory, AbilityState, ActiveAbility,
+/// };
+///
+/// let mut world = World::new();
+/// world.insert_resource(Events::<CooldownStarted>::default());
+/// let fireball = world.spawn().insert(Ability).insert(Cooldown::new(2.0)).id();
+/// let fire_blast = world
+///     .spawn()
+///     .insert(Ability)
+///     .insert(Cooldown::new(5.0))
+///     .insert(LinkedCooldowns(vec![fireball]))
+///     .id();
+/// // The link is mutual
+/// world.entity_mut(fireball).insert(LinkedCooldowns(vec![fire_blast]));
+///
+/// let mut abilities = Abilities::from_ability_list(vec![fireball, fire_blast]);
+/// abilities.active_ability = ActiveAbility::new(fire_blast, AbilityState::JustStarted);
+/// let caster = world.spawn().insert(abilities).id();
+///
+/// let mut stage = SystemStage::single(start_linked_cooldowns);
+/// let mut reader = ManualEventReader::<CooldownStarted>::default();
+/// stage.run(&mut world);
+///
+/// let events = world.get_resource::<Events<CooldownStarted>>().unwrap();
+/// let started: Vec<_> = reader.iter(events).copied().collect();
+/// assert_eq!(started, vec![CooldownStarted { ability: fireball }]);
+///
+/// // Fireball cannot be used until its own cooldown has elapsed
+/// let fireball_cooldown = world.get::<Cooldown>(fireball).unwrap();
+/// assert!(!fireball_cooldown.ready());
+/// assert_eq!(fireball_cooldown.remaining_seconds(), 2.0);
+/// // Fire blast has no `CooldownTrigger`, so its own cooldown was not started
+/// assert_eq!(world.get::<Cooldown>(fire_blast).unwrap().charges(), 1);
+///
+/// // When both abilities are used at once, each starts the other, spending one charge each
+/// world.get_mut::<Cooldown>(fireball).unwrap().refresh();
+/// let mut abilities = world.get_mut::<Abilities>(caster).unwrap();
+/// *abilities.slot_mut(AbilityCategory("off_hand")) =
+///     ActiveAbility::new(fireball, AbilityState::JustStarted);
+/// stage.run(&mut world);
+///
+/// let events = world.get_resource::<Events<CooldownStarted>>().unwrap();
+/// assert_eq!(reader.iter(events).count(), 2);
+/// assert_eq!(world.get::<Cooldown>(fireball).unwrap().charges(), 0);
+/// assert_eq!(world.get::<Cooldown>(fire_blast).unwrap().charges(), 0);
+///
+/// // Abilities started by their own `CooldownTrigger` are left to `trigger_cooldowns`,
+/// // rather than being started a second time by their links
+/// for ability in [fireball, fire_blast] {
+///     world.get_mut::<Cooldown>(ability).unwrap().refresh();
+///     world.entity_mut(ability).insert(CooldownTrigger::OnStart);
+/// }
+/// stage.run(&mut world);
+///
+/// let events = world.get_resource::<Events<CooldownStarted>>().unwrap();
+/// assert_eq!(reader.iter(events).count(), 0);
+/// assert_eq!(world.get::<Cooldown>(fireball).unwrap().charges(), 1);
+/// assert_eq!(world.get::<Cooldown>(fire_blast).unwrap().charges(), 1);
+/// ```
+pub fn start_linked_cooldowns(
+    unit_query: Query<&Abilities>,
+    link_query: Query<&LinkedCooldowns, With<Ability>>,
+    trigger_query: Query<&CooldownTrigger, With<Ability>>,
+    mut cooldown_query: Query<&mut Cooldown, With<Ability>>,
+    mut event_writer: EventWriter<CooldownStarted>,
+) {
+    // Abilities used this frame with `CooldownTrigger::OnStart` are started by `trigger_cooldowns`
+    let mut started: HashSet<Entity> = unit_query
+        .iter()
+        .flat_map(|abilities| abilities.active_abilities())
+        .filter(|active_ability| active_ability.state == AbilityState::JustStarted)
+        .filter_map(|active_ability| active_ability.entity)
+        .filter(|&ability_entity| {
+            trigger_query.get(ability_entity).ok() == Some(&CooldownTrigger::OnStart)
+        })
+        .collect();
+
+    for abilities in unit_query.iter() {
+        for active_ability in abilities.active_abilities() {
+            if active_ability.state != AbilityState::JustStarted {
+                continue;
+            }
+
+            let ability_entity = match active_ability.entity {
+                Some(ability_entity) => ability_entity,
+                None => continue,
+            };
+
+            if let Ok(linked_cooldowns) = link_query.get(ability_entity) {
+                for &linked_entity in linked_cooldowns.0.iter() {
+                    // Also guards against abilities that link to each other
+                    if !started.insert(linked_entity) {
+                        continue;
+                    }
+
+                    if let Ok(mut cooldown) = cooldown_query.get_mut(linked_entity) {
+                        cooldown.start();
+                        event_writer.send(CooldownStarted {
+                            ability: linked_entity,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Spends a charge from the `Cooldown` of a shared pool entity whenever this ability is used
+///
+/// Unlike `LinkedCooldowns`, the charges themselves are shared:
+/// every ability drawing from the pool is unusable while it is empty,
+/// and the pool recharges according to its own `Cooldown`.
+/// The pool entity should not be an `Ability`.
+///
+/// ```rust
+/// use bevy::app::Events;
+/// use bevy::ecs::system::CommandQueue;
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::cooldowns::{
+///     check_shared_charges, spend_shared_charges, Cooldown, CooldownStarted, SharedCharges,
+/// };
+/// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+/// use leafwing_abilities::abilities::systems::set_all_abilities_usable;
+/// use leafwing_abilities::abilities::usability::{UsabilityReason, Usable};
+/// use leafwing_abilities::abilities::{Abilities, AbilityState, ActiveAbility};
+///
+/// let mut world = World::new();
+/// world.insert_resource(Events::<CooldownStarted>::default());
+/// let spell_charges = world.spawn().insert(Cooldown::new_with_charges(10.0, 2)).id();
+///
+/// let mut command_queue = CommandQueue::default();
+/// let mut commands = Commands::new(&mut command_queue, &world);
+/// let fireball = commands.spawn_ability().with(SharedCharges(spell_charges)).id();
+/// let frostbolt = commands.spawn_ability().with(SharedCharges(spell_charges)).id();
+/// command_queue.apply(&mut world);
+///
+/// let caster = world
+///     .spawn()
+///     .insert(Abilities::from_ability_list(vec![fireball, frostbolt]))
+///     .id();
+///
+/// let mut spend = SystemStage::single(spend_shared_charges);
+/// for ability in [fireball, frostbolt] {
+///     let mut abilities = world.get_mut::<Abilities>(caster).unwrap();
+///     abilities.active_ability = ActiveAbility::new(ability, AbilityState::JustStarted);
+///     spend.run(&mut world);
+/// }
+/// assert_eq!(world.get::<Cooldown>(spell_charges).unwrap().charges(), 0);
+///
+/// // Both spells are unusable until the shared pool recharges
+/// SystemStage::single(set_all_abilities_usable).run(&mut world);
+/// SystemStage::single(check_shared_charges).run(&mut world);
+/// for ability in [fireball, frostbolt] {
+///     let usable = world.get::<Usable>(ability).unwrap();
+///     assert_eq!(usable.reason(), UsabilityReason::OnCooldown);
+/// }
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SharedCharges(pub Entity);
+
+/// Marks abilities as unusable while the pool of their `SharedCharges` is empty
+pub fn check_shared_charges(
+    mut ability_query: Query<(&SharedCharges, &mut Usable), With<Ability>>,
+    pool_query: Query<&Cooldown>,
+) {
+    for (&SharedCharges(pool), mut usable) in ability_query.iter_mut() {
+        if let Ok(cooldown) = pool_query.get(pool) {
+            if cooldown.charges() == 0 {
+                usable.block(UsabilityReason::OnCooldown);
+            }
+        }
+    }
+}
+
+/// Spends a shared charge for each ability with `SharedCharges` that was just used
+pub fn spend_shared_charges(
+    unit_query: Query<&Abilities>,
+    ability_query: Query<&SharedCharges, With<Ability>>,
+    mut pool_query: Query<&mut Cooldown>,
+    mut event_writer: EventWriter<CooldownStarted>,
+) {
+    for abilities in unit_query.iter() {
+        for active_ability in abilities.active_abilities() {
+            if active_ability.state != AbilityState::JustStarted {
+                continue;
+            }
+
+            if let Some(&SharedCharges(pool)) = active_ability
+                .entity
+                .and_then(|ability_entity| ability_query.get(ability_entity).ok())
+            {
+                if let Ok(mut cooldown) = pool_query.get_mut(pool) {
+                    cooldown.start();
+                    event_writer.send(CooldownStarted { ability: pool });
+                }
+            }
+        }
+    }
+}
+
+/// Controls when the `Cooldown` of an ability is started automatically
+///
+/// Abilities without this component never have their cooldown started for them:
+/// call `Cooldown::start` from your own systems instead.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CooldownTrigger {
+    /// Started as soon as the ability reaches `AbilityState::JustStarted`
+    OnStart,
+    /// Started once the ability stops being active, unless it was cancelled
+    ///
+    /// Interrupted casts and channels do not waste their cooldown.
+    OnComplete,
+    /// Started by your effect systems, using `CooldownTrigger::on_hit`
+    OnHit,
+}
+
+impl CooldownTrigger {
+    /// Starts the cooldown if it is triggered by hits
+    ///
+    /// Call this whenever the ability lands, regardless of its trigger.
+    /// This does not send `CooldownStarted`: send a `StartCooldown` event for `OnHit` abilities instead if you need it.
+    pub fn on_hit(&self, cooldown: &mut Cooldown) {
+        if *self == CooldownTrigger::OnHit {
+            cooldown.start();
+        }
+    }
+}
+
+/// Scales the cooldown started by a `CooldownTrigger` with the `ActiveAbility::charge` of the ability,
+/// so that partially charged releases recover sooner
+///
+/// The cooldown is interpolated from `min_cooldown` at no charge to `max_cooldown` at full charge.
+/// Cooldowns started by other means use the usual duration of their `Cooldown`.
+///
+/// ```rust
+/// use bevy::app::Events;
+/// use bevy::prelude::*;
+/// use core::time::Duration;
+/// use leafwing_abilities::abilities::cooldowns::{
+///     trigger_cooldowns, ChargeScaledCooldown, Cooldown, CooldownStarted, CooldownTrigger,
+/// };
+/// use leafwing_abilities::abilities::{Abilities, Ability, AbilityCancelled, AbilityState, ActiveAbility};
+///
+/// let mut world = World::new();
+/// world.insert_resource(Events::<AbilityCancelled>::default());
+/// world.insert_resource(Events::<CooldownStarted>::default());
+///
+/// let charged_shot = world
+///     .spawn()
+///     .insert(Ability)
+///     .insert(Cooldown::new(8.0))
+///     .insert(CooldownTrigger::OnStart)
+///     .insert(ChargeScaledCooldown {
+///         min_cooldown: Duration::from_secs(2),
+///         max_cooldown: Duration::from_secs(8),
+///     })
+///     .id();
+///
+/// let mut abilities = Abilities::from_ability_list(vec![charged_shot]);
+/// abilities.active_ability =
+///     ActiveAbility::new(charged_shot, AbilityState::JustStarted).with_charge(0.5);
+/// world.spawn().insert(abilities);
+///
+/// SystemStage::single(trigger_cooldowns).run(&mut world);
+///
+/// // Released at half charge, halfway between the minimum and maximum cooldown
+/// let remaining = world.get::<Cooldown>(charged_shot).unwrap().remaining_seconds();
+/// assert!((remaining - 5.0).abs() < 1e-4);
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChargeScaledCooldown {
+    pub min_cooldown: Duration,
+    pub max_cooldown: Duration,
+}
+
+impl ChargeScaledCooldown {
+    /// The cooldown for an ability released at the provided charge level, from 0.0 to 1.0
+    pub fn duration_for(&self, charge: f32) -> Duration {
+        let charge = charge.clamp(0.0, 1.0);
+        let min_seconds = self.min_cooldown.as_secs_f32();
+        let max_seconds = self.max_cooldown.as_secs_f32();
+        Duration::from_secs_f32(min_seconds + (max_seconds - min_seconds) * charge)
+    }
+
+    /// Starts the provided cooldown, scaled by `charge` if the ability has a `ChargeScaledCooldown`
+    fn start(charge_scaled: Option<&Self>, cooldown: &mut Cooldown, charge: f32) {
+        match charge_scaled {
+            Some(charge_scaled) => cooldown.start_with(charge_scaled.duration_for(charge)),
+            None => cooldown.start(),
+        }
+    }
+}
+
+/// What a `CooldownPolicy` knows about the use of an ability whose cooldown is about to be started
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CooldownContext {
+    pub caster: Entity,
+    pub ability: Entity,
+    /// The trigger which is starting the cooldown, either `OnStart` or `OnComplete`
+    pub trigger: CooldownTrigger,
+    /// The `ActiveAbility::charge` the ability was used with
+    pub charge: f32,
+}
+
+/// Decides whether `trigger_cooldowns` should start the cooldown of an ability,
+/// such as skipping the cooldown of an attack that missed
+///
+/// Attach a policy to an ability with `CustomCooldownPolicy`.
+pub trait CooldownPolicy: Send + Sync + 'static {
+    fn should_start(&self, ctx: &CooldownContext) -> bool;
+}
+
+/// The default `CooldownPolicy`, which always starts the cooldown
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct AlwaysStart;
+
+impl CooldownPolicy for AlwaysStart {
+    fn should_start(&self, _ctx: &CooldownContext) -> bool {
+        true
+    }
+}
+
+/// The `CooldownPolicy` of an ability, consulted each time its `CooldownTrigger` would start its cooldown
+///
+/// Abilities without this component behave as if they had the `AlwaysStart` policy.
+/// The policy can be reached again with `get_mut`, so that your effect systems can update its state.
+///
+/// ```rust
+/// use bevy::app::Events;
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::cooldowns::{
+///     trigger_cooldowns, Cooldown, CooldownContext, CooldownPolicy, CooldownStarted,
+///     CooldownTrigger, CustomCooldownPolicy,
+/// };
+/// use leafwing_abilities::abilities::{Abilities, Ability, AbilityCancelled, AbilityState, ActiveAbility};
+///
+/// #[derive(Default)]
+/// struct NoCooldownOnMiss {
+///     missed: bool,
+/// }
+///
+/// impl CooldownPolicy for NoCooldownOnMiss {
+///     fn should_start(&self, _ctx: &CooldownContext) -> bool {
+///         !self.missed
+///     }
+/// }
+///
+/// let mut world = World::new();
+/// world.insert_resource(Events::<AbilityCancelled>::default());
+/// world.insert_resource(Events::<CooldownStarted>::default());
+///
+/// let snipe = world
+///     .spawn()
+///     .insert(Ability)
+///     .insert(Cooldown::new(10.0))
+///     .insert(CooldownTrigger::OnComplete)
+///     .insert(CustomCooldownPolicy::new(NoCooldownOnMiss::default()))
+///     .id();
+///
+/// let mut abilities = Abilities::from_ability_list(vec![snipe]);
+/// abilities.active_ability = ActiveAbility::new(snipe, AbilityState::Active);
+/// let caster = world.spawn().insert(abilities).id();
+///
+/// let mut stage = SystemStage::single(trigger_cooldowns);
+/// stage.run(&mut world);
+///
+/// // The shot misses, and then completes
+/// world
+///     .get_mut::<CustomCooldownPolicy>(snipe)
+///     .unwrap()
+///     .get_mut::<NoCooldownOnMiss>()
+///     .unwrap()
+///     .missed = true;
+/// world.get_mut::<Abilities>(caster).unwrap().active_ability = ActiveAbility::NONE;
+/// stage.run(&mut world);
+///
+/// assert!(world.get::<Cooldown>(snipe).unwrap().ready());
+/// ```
+#[derive(Component)]
+pub struct CustomCooldownPolicy {
+    policy: Box<dyn Any + Send + Sync>,
+    should_start: fn(&(dyn Any + Send + Sync), &CooldownContext) -> bool,
+}
+
+impl CustomCooldownPolicy {
+    pub fn new<P: CooldownPolicy>(policy: P) -> Self {
+        Self {
+            policy: Box::new(policy),
+            should_start: Self::consult::<P>,
+        }
+    }
+
+    /// Whether the cooldown of the ability should be started
+    pub fn should_start(&self, ctx: &CooldownContext) -> bool {
+        (self.should_start)(&*self.policy, ctx)
+    }
+
+    /// The policy, if it is a `P`
+    pub fn get<P: CooldownPolicy>(&self) -> Option<&P> {
+        self.policy.downcast_ref::<P>()
+    }
+
+    /// The policy, if it is a `P`
+    pub fn get_mut<P: CooldownPolicy>(&mut self) -> Option<&mut P> {
+        self.policy.downcast_mut::<P>()
+    }
+
+    fn consult<P: CooldownPolicy>(policy: &(dyn Any + Send + Sync), ctx: &CooldownContext) -> bool {
+        policy
+            .downcast_ref::<P>()
+            .map_or(true, |policy| policy.should_start(ctx))
+    }
+
+    /// Consults the provided policy, starting the cooldown if there is none
+    fn allows(custom_policy: Option<&Self>, ctx: &CooldownContext) -> bool {
+        custom_policy.map_or(true, |custom_policy| custom_policy.should_start(ctx))
+    }
+}
+
+impl Default for CustomCooldownPolicy {
+    fn default() -> Self {
+        Self::new(AlwaysStart)
+    }
+}
+
+/// Starts the cooldowns of abilities with the `OnStart` and `OnComplete` `CooldownTrigger`s,
+/// unless their `CustomCooldownPolicy` declines
+pub fn trigger_cooldowns(
+    unit_query: Query<(Entity, &Abilities)>,
+    mut ability_query: Query<
+        (
+            &CooldownTrigger,
+            &mut Cooldown,
+            Option<&ChargeScaledCooldown>,
+            Option<&CustomCooldownPolicy>,
+        ),
+        With<Ability>,
+    >,
+    mut cancelled_reader: EventReader<AbilityCancelled>,
+    mut event_writer: EventWriter<CooldownStarted>,
+    // The abilities of each unit which had taken effect as of the previous frame, with their charge levels
+    mut previously_started: Local<HashMap<Entity, HashMap<Entity, f32>>>,
+) {
+    let cancelled: HashSet<(Entity, Entity)> = cancelled_reader
+        .iter()
+        .map(|event| (event.caster, event.ability))
+        .collect();
+
+    let mut currently_started: HashMap<Entity, HashMap<Entity, f32>> = HashMap::default();
+
+    for (unit_entity, abilities) in unit_query.iter() {
+        let started = currently_started.entry(unit_entity).or_default();
+
+        for active_ability in abilities.active_abilities() {
+            let ability_entity = match active_ability.entity {
+                Some(ability_entity) => ability_entity,
+                None => continue,
+            };
+
+            match active_ability.state {
+                AbilityState::JustStarted => {
+                    if let Ok((CooldownTrigger::OnStart, mut cooldown, charge_scaled, policy)) =
+                        ability_query.get_mut(ability_entity)
+                    {
+                        let ctx = CooldownContext {
+                            caster: unit_entity,
+                            ability: ability_entity,
+                            trigger: CooldownTrigger::OnStart,
+                            charge: active_ability.charge,
+                        };
+                        if CustomCooldownPolicy::allows(policy, &ctx) {
+                            ChargeScaledCooldown::start(
+                                charge_scaled,
+                                &mut cooldown,
+                                active_ability.charge,
+                            );
+                            event_writer.send(CooldownStarted {
+                                ability: ability_entity,
+                            });
+                        }
+                    }
+                    started.insert(ability_entity, active_ability.charge);
+                }
+                AbilityState::Active => {
+                    started.insert(ability_entity, active_ability.charge);
+                }
+                AbilityState::Casting | AbilityState::Idle => (),
+            }
+        }
+    }
+
+    for (unit_entity, previous) in previously_started.iter() {
+        let current = currently_started.get(unit_entity);
+        for (&ability_entity, &charge) in previous.iter() {
+            let still_active =
+                current.map_or(false, |current| current.contains_key(&ability_entity));
+            if still_active || cancelled.contains(&(*unit_entity, ability_entity)) {
+                continue;
+            }
+
+            if let Ok((CooldownTrigger::OnComplete, mut cooldown, charge_scaled, policy)) =
+                ability_query.get_mut(ability_entity)
+            {
+                let ctx = CooldownContext {
+                    caster: *unit_entity,
+                    ability: ability_entity,
+                    trigger: CooldownTrigger::OnComplete,
+                    charge,
+                };
+                if CustomCooldownPolicy::allows(policy, &ctx) {
+                    ChargeScaledCooldown::start(charge_scaled, &mut cooldown, charge);
+                    event_writer.send(CooldownStarted {
+                        ability: ability_entity,
+                    });
+                }
+            }
+        }
+    }
+
+    *previously_started = currently_started;
+}
+
+/// Refreshes the cooldowns of every ability of the provided unit
+///
+/// Useful for respawns and effects that reset all cooldowns.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::cooldowns::{refresh_cooldowns, Cooldown};
+/// use leafwing_abilities::abilities::{Abilities, Ability};
+///
+/// let mut world = World::new();
+/// let mut spawn_cooldown = |mut cooldown: Cooldown| {
+///     while cooldown.ready() {
+///         cooldown.start();
+///     }
+///     world.spawn().insert(Ability).insert(cooldown).id()
+/// };
+/// let meteor = spawn_cooldown(Cooldown::new(60.0));
+/// let fireball = spawn_cooldown(Cooldown::new(2.0));
+/// let blink = spawn_cooldown(Cooldown::new_with_charges(10.0, 3));
+/// world
+///     .spawn()
+///     .insert(Abilities::from_ability_list(vec![meteor, fireball, blink]));
+///
+/// fn respawn(unit_query: Query<&Abilities>, mut cooldown_query: Query<&mut Cooldown>) {
+///     for abilities in unit_query.iter() {
+///         refresh_cooldowns(abilities, &mut cooldown_query);
+///     }
+/// }
+///
+/// SystemStage::single(respawn).run(&mut world);
+/// for ability in [meteor, fireball, blink] {
+///     let cooldown = world.get::<Cooldown>(ability).unwrap();
+///     assert!(cooldown.finished());
+///     assert_eq!(cooldown.charges(), cooldown.max_charges());
+/// }
+/// ```
+pub fn refresh_cooldowns(abilities: &Abilities, cooldown_query: &mut Query<&mut Cooldown>) {
+    for ability_entity in abilities.ability_list() {
+        if let Ok(mut cooldown) = cooldown_query.get_mut(ability_entity) {
+            cooldown.refresh();
+        }
+    }
+}
+
+/// The seconds until each ability of the provided unit can be used again, soonest first, such as for AI planning
+///
+/// Abilities with a charge available are ready now, and report `0.0`.
+/// Abilities without a `Cooldown` are skipped, and ties keep the order of `Abilities::ability_list`.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use core::time::Duration;
+/// use leafwing_abilities::abilities::cooldowns::{cooldown_snapshot, Cooldown};
+/// use leafwing_abilities::abilities::{Abilities, Ability};
+///
+/// let mut world = World::new();
+/// let mut spawn_cooldown = |seconds, started: bool| {
+///     let mut cooldown = Cooldown::new(seconds);
+///     if started {
+///         cooldown.start();
+///     }
+///     world.spawn().insert(Ability).insert(cooldown).id()
+/// };
+/// let meteor = spawn_cooldown(5.0, true);
+/// let fireball = spawn_cooldown(2.0, true);
+/// let frostbolt = spawn_cooldown(8.0, false);
+/// world
+///     .spawn()
+///     .insert(Abilities::from_ability_list(vec![meteor, fireball, frostbolt]));
+///
+/// fn plan(unit_query: Query<&Abilities>, cooldown_query: Query<&Cooldown>) {
+///     for abilities in unit_query.iter() {
+///         let remaining: Vec<f32> = cooldown_snapshot(abilities, &cooldown_query)
+///             .into_iter()
+///             .map(|(_ability, remaining)| remaining)
+///             .collect();
+///         assert_eq!(remaining, vec![0.0, 2.0, 5.0]);
+///     }
+/// }
+///
+/// SystemStage::single(plan).run(&mut world);
+/// ```
+pub fn cooldown_snapshot(
+    abilities: &Abilities,
+    cooldown_query: &Query<&Cooldown>,
+) -> Vec<(Entity, f32)> {
+    let mut snapshot: Vec<(Entity, f32)> = abilities
+        .ability_list()
+        .into_iter()
+        .filter_map(|ability_entity| {
+            let cooldown = cooldown_query.get(ability_entity).ok()?;
+            let remaining = if cooldown.ready() {
+                0.0
+            } else {
+                cooldown.remaining_seconds()
+            };
+            Some((ability_entity, remaining))
+        })
+        .collect();
+
+    // Sorting is stable, so abilities that are ready at the same time keep their relative order
+    snapshot.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+    snapshot
+}
+
+/// A short cooldown shared by every ability of a unit, started whenever any of them is used
+///
+/// Stored on the unit entity, alongside its `Abilities`.
+#[derive(Component, Clone)]
+pub struct GlobalCooldown {
+    timer: Timer,
+}
+
+impl GlobalCooldown {
+    pub fn new(seconds: f32) -> Self {
+        let mut timer = Timer::from_seconds(seconds, false);
+        // Units should be able to act immediately when spawned
+        timer.tick(Duration::from_secs_f32(seconds));
+
+        Self { timer }
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        self.timer.tick(delta);
+    }
+
+    pub fn start(&mut self) {
+        self.timer.reset();
+    }
+
+    pub fn remaining_seconds(&self) -> f32 {
+        self.timer.duration().as_secs_f32() - self.timer.elapsed_secs()
+    }
+
+    pub fn finished(&self) -> bool {
+        self.timer.finished()
+    }
+}
+
+/// Marker component for abilities which neither trigger nor respect the `GlobalCooldown` of their unit
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IgnoresGlobalCooldown;
+
+pub(crate) fn tick_global_cooldowns(
+    mut query: Query<&mut GlobalCooldown>,
+    time: Res<Time>,
+    timestep: Option<Res<AbilityTimestep>>,
+) {
+    let delta = ability_delta(&time, timestep);
+    for mut global_cooldown in query.iter_mut() {
+        // Extra check here avoids change-detection false positives
+        if !global_cooldown.finished() {
+            global_cooldown.tick(delta);
+        }
+    }
+}
+
+pub(crate) fn start_global_cooldowns(
+    mut unit_query: Query<(&Abilities, &mut GlobalCooldown)>,
+    ability_query: Query<Entity, (With<Ability>, Without<IgnoresGlobalCooldown>)>,
+) {
+    for (abilities, mut global_cooldown) in unit_query.iter_mut() {
+        for active_ability in abilities.active_abilities() {
+            if active_ability.state != AbilityState::JustStarted {
+                continue;
+            }
+
+            if let Some(ability_entity) = active_ability.entity {
+                if ability_query.get(ability_entity).is_ok() {
+                    global_cooldown.start();
+                }
+            }
+        }
+    }
+}
+
+/// The `GateCheck` matching `check_global_cooldowns`
+pub fn global_cooldown_gate(
+    world: &World,
+    caster: Entity,
+    ability: Entity,
+) -> Option<UsabilityReason> {
+    let running = world
+        .get::<GlobalCooldown>(caster)
+        .map_or(false, |global_cooldown| !global_cooldown.finished());
+    let ignored = world.get::<IgnoresGlobalCooldown>(ability).is_some();
+
+    if running && !ignored {
+        Some(UsabilityReason::OnGlobalCooldown)
+    } else {
+        None
+    }
+}
+
+/// Marks every ability of a unit as unusable while its global cooldown is running
+///
+/// This operates on the unit's `Abilities` directly, rather than on the shared `Usable` components,
+/// so it must run after those have been collected.
+pub(crate) fn check_global_cooldowns(
+    mut unit_query: Query<(&mut Abilities, &GlobalCooldown)>,
+    ability_query: Query<Entity, (With<Ability>, Without<IgnoresGlobalCooldown>)>,
+) {
+    for (mut abilities, global_cooldown) in unit_query.iter_mut() {
+        if global_cooldown.finished() {
+            continue;
+        }
+
+        for ability_entity in abilities.ability_list() {
+            if ability_query.get(ability_entity).is_ok() {
+                abilities.block_usable(ability_entity, UsabilityReason::OnGlobalCooldown);
+            }
+        }
+    }
+}
@@ -1,3 +1,2 @@
 pub mod abilities;
-pub mod input;
 pub mod resources;
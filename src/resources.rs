@@ -1,38 +1,165 @@
 use bevy::prelude::*;
+use bevy::utils::HashMap;
+use core::any::TypeId;
 use core::convert::From;
 use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::time::Duration;
 use std::{
     cmp::{Ordering, PartialEq, PartialOrd},
     marker::PhantomData,
 };
 
-use crate::abilities::{usability::Usable, Abilities, Ability, AbilityLabel, AbilityState};
+use crate::abilities::cooldowns::OverclockReduction;
+use crate::abilities::{
+    ability_delta,
+    passive::Passive,
+    usability::{GateChecks, UsabilityReason, Usable},
+    Abilities, Ability, AbilityCancelled, AbilityLabel, AbilityStages, AbilityState,
+    AbilityTimestep, ActiveAbility, LocalTimeScale,
+};
 
 pub trait ResourcePoolExt {
+    /// Adds the systems for `ResourcePool<R>` to the default `AbilityStages`
     fn add_resource_pool<R: ResourceType + From<f32> + Into<f32>>(&mut self) -> &mut Self;
+
+    /// Adds the systems for `ResourcePool<R>` to the provided stages,
+    /// which should match those of the `AbilitiesPlugin`
+    fn add_resource_pool_in_stages<R: ResourceType + From<f32> + Into<f32>, S: StageLabel + Clone>(
+        &mut self,
+        stages: AbilityStages<S>,
+    ) -> &mut Self;
+
+    /// Inserts the marker component `M` on units whose `ResourcePool<R>` is below `fraction` of its maximum,
+    /// and removes it once they recover
+    ///
+    /// The marker is updated in the `post` stage of the default `AbilityStages`.
+    /// Each pair of `R` and `M` may only have one threshold: adding it again replaces the fraction.
+    fn add_resource_threshold<R: ResourceType + Into<f32>, M: Component + Default>(
+        &mut self,
+        fraction: f32,
+    ) -> &mut Self;
+
+    /// Adds a resource threshold, as in `add_resource_threshold`,
+    /// with its marker system in the `post` stage of the provided stages
+    fn add_resource_threshold_in_stages<
+        R: ResourceType + Into<f32>,
+        M: Component + Default,
+        S: StageLabel + Clone,
+    >(
+        &mut self,
+        fraction: f32,
+        stages: AbilityStages<S>,
+    ) -> &mut Self;
 }
 
 impl ResourcePoolExt for App {
     fn add_resource_pool<R: ResourceType + From<f32> + Into<f32>>(&mut self) -> &mut Self {
+        self.add_resource_pool_in_stages::<R, CoreStage>(AbilityStages::default())
+    }
+
+    fn add_resource_pool_in_stages<
+        R: ResourceType + From<f32> + Into<f32>,
+        S: StageLabel + Clone,
+    >(
+        &mut self,
+        stages: AbilityStages<S>,
+    ) -> &mut Self {
+        // These events are shared between all resource types, and must only be added once
+        if !self.world.contains_resource::<Events<ResourceEmptied>>() {
+            self.add_event::<ResourceEmptied>();
+        }
+        if !self.world.contains_resource::<Events<ResourceFilled>>() {
+            self.add_event::<ResourceFilled>();
+        }
+
+        self.register_type::<ResourcePool<R>>();
+        self.world
+            .get_resource_or_insert_with(GateChecks::default)
+            .add(resource_gate::<R>);
+
         self.add_system_to_stage(
-            CoreStage::PreUpdate,
+            stages.main.clone(),
+            tick_regen_modifiers::<R>
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
             regen_resource::<R>
                 .label(AbilityLabel::Maintain)
                 .before(AbilityLabel::Check),
         )
         .add_system_to_stage(
-            CoreStage::PreUpdate,
+            stages.main.clone(),
+            tick_regen_resource::<R>
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            drain_channeled_resource::<R>
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            refund_resource::<R>
+                .label(AbilityLabel::Maintain)
+                .before(AbilityLabel::Check),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
             check_resource::<R>
                 .label(AbilityLabel::Check)
                 .before(AbilityLabel::Decide),
         )
-        .add_system(spend_resource::<R>)
+        .add_system_to_stage(
+            stages.main.clone(),
+            spend_resource::<R>
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(
+            stages.main.clone(),
+            gain_resource_on_use::<R>
+                .label(AbilityLabel::Effect)
+                .after(AbilityLabel::Decide),
+        )
+        .add_system_to_stage(stages.post.clone(), detect_resource_thresholds::<R>)
+    }
+
+    fn add_resource_threshold<R: ResourceType + Into<f32>, M: Component + Default>(
+        &mut self,
+        fraction: f32,
+    ) -> &mut Self {
+        self.add_resource_threshold_in_stages::<R, M, CoreStage>(fraction, AbilityStages::default())
+    }
+
+    fn add_resource_threshold_in_stages<
+        R: ResourceType + Into<f32>,
+        M: Component + Default,
+        S: StageLabel + Clone,
+    >(
+        &mut self,
+        fraction: f32,
+        stages: AbilityStages<S>,
+    ) -> &mut Self {
+        let already_added = self.world.contains_resource::<ResourceThreshold<R, M>>();
+        self.insert_resource(ResourceThreshold::<R, M>::new(fraction));
+
+        if !already_added {
+            self.add_system_to_stage(stages.post, apply_threshold_marker::<R, M>);
+        }
+        self
     }
 }
 
 /// Marker trait for resource types (like Life, Mana, Energy, Rage...)
+///
+/// Resource types must be `Reflect`, so that their `ResourcePool` can be inspected and saved.
 pub trait ResourceType:
     Component
+    + Reflect
     + Clone
     + Copy
     + PartialOrd
@@ -47,23 +174,809 @@ pub trait ResourceType:
     const LOGICAL_MAX: Self;
 }
 
-#[derive(Component, PartialEq)]
+/// The amount of the resource `R` that must be paid to use an ability
+///
+/// Abilities may have costs in several resource types at once.
+/// Each registered resource pool is checked independently by `check_resource`,
+/// so an ability is only usable if every one of its costs can be paid.
+/// As `spend_resource` runs directly after abilities are chosen,
+/// either all of the costs of an ability are paid or none of them are.
+///
+/// ```rust
+/// use bevy::ecs::system::CommandQueue;
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+/// use leafwing_abilities::abilities::systems::set_all_abilities_usable;
+/// use leafwing_abilities::abilities::usability::{UsabilityReason, Usable};
+/// use leafwing_abilities::abilities::Abilities;
+/// use leafwing_abilities::resources::doc_resources::{Life, Mana};
+/// use leafwing_abilities::resources::{check_resource, spend_resource, ResourcePool};
+///
+/// let mut world = World::new();
+/// let mut command_queue = CommandQueue::default();
+/// let mut commands = Commands::new(&mut command_queue, &world);
+/// let blood_pact = commands
+///     .spawn_ability()
+///     .with_cost(Mana(20))
+///     .with_cost(Life(50))
+///     .id();
+/// command_queue.apply(&mut world);
+///
+/// // Enough mana, but too little life
+/// let caster = world
+///     .spawn()
+///     .insert(Abilities::from_ability_list(vec![blood_pact]))
+///     .insert(ResourcePool::new(Mana(100), Mana(100), Mana(0)))
+///     .insert(ResourcePool::new(Life(10), Life(100), Life(0)))
+///     .id();
+///
+/// SystemStage::single(set_all_abilities_usable).run(&mut world);
+/// SystemStage::single(check_resource::<Mana>).run(&mut world);
+/// SystemStage::single(check_resource::<Life>).run(&mut world);
+/// let usable = *world.get::<Usable>(blood_pact).unwrap();
+/// assert_eq!(usable.reason(), UsabilityReason::InsufficientResource);
+///
+/// // Only usable abilities are chosen, so nothing is spent
+/// if usable.is_usable() {
+///     let mut abilities = world.get_mut::<Abilities>(caster).unwrap();
+///     abilities.activate(blood_pact).unwrap();
+/// }
+/// SystemStage::single(spend_resource::<Mana>).run(&mut world);
+/// SystemStage::single(spend_resource::<Life>).run(&mut world);
+///
+/// assert_eq!(world.get::<ResourcePool<Mana>>(caster).unwrap().current(), Mana(100));
+/// assert_eq!(world.get::<ResourcePool<Life>>(caster).unwrap().current(), Life(10));
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct AbilityCost<R: ResourceType>(pub R);
+
+/// A cost which spends any surplus of `R`, beyond the `base` cost, to shorten the cooldown of the ability
+///
+/// Each `per_second_reduction` of surplus shortens the cooldown by one second, up to `max_reduction`.
+/// The surplus is measured from the pool before any costs of the ability are paid,
+/// as `spend_resource` computes and pays every cost of `R` at once, directly after abilities are chosen.
+/// The cooldown itself is shortened in `AbilityStages::post`, once it has been started.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct OverclockCost<R: ResourceType> {
+    pub base: R,
+    pub per_second_reduction: R,
+    pub max_reduction: Duration,
+}
+
+impl<R: ResourceType + Into<f32>> OverclockCost<R> {
+    /// The surplus spent from `available` resources, and the resulting cooldown reduction
+    pub fn overclock(&self, available: R) -> (R, Duration) {
+        let per_second_reduction: f32 = self.per_second_reduction.into();
+        if available <= self.base || per_second_reduction <= 0.0 {
+            return (R::ZERO, Duration::ZERO);
+        }
+
+        let max_surplus = per_second_reduction * self.max_reduction.as_secs_f32();
+        let surplus: f32 = (available - self.base).into();
+        let surplus = surplus.min(max_surplus);
+        (
+            surplus.into(),
+            Duration::from_secs_f32(surplus / per_second_reduction),
+        )
+    }
+}
+
+/// A cost which spends as much of `R` as is available, up to `max`
+///
+/// Abilities with this cost are only usable with at least `min` available,
+/// after any other costs of the ability have been paid.
+/// The amount actually spent is recorded in `LastSpent<R>`, for effects that scale with it.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::{Abilities, Ability, AbilityState, ActiveAbility};
+/// use leafwing_abilities::resources::doc_resources::Rage;
+/// use leafwing_abilities::resources::{spend_resource, FlexibleCost, LastSpent, ResourcePool};
+///
+/// let mut world = World::new();
+/// let rampage = world
+///     .spawn()
+///     .insert(Ability)
+///     .insert(FlexibleCost { min: Rage(10), max: Rage(50) })
+///     .id();
+///
+/// let mut abilities = Abilities::from_ability_list(vec![rampage]);
+/// abilities.active_ability = ActiveAbility::new(rampage, AbilityState::JustStarted);
+/// let caster = world
+///     .spawn()
+///     .insert(abilities)
+///     .insert(ResourcePool::new(Rage(30), Rage(100), Rage(0)))
+///     .id();
+///
+/// SystemStage::single(spend_resource::<Rage>).run(&mut world);
+///
+/// // Only the available rage was spent
+/// assert_eq!(world.get::<ResourcePool<Rage>>(caster).unwrap().current(), Rage(0));
+/// assert_eq!(world.get::<LastSpent<Rage>>(rampage), Some(&LastSpent(Rage(30))));
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct FlexibleCost<R: ResourceType> {
+    pub min: R,
+    pub max: R,
+}
+
+/// A cost paid as a fraction of the maximum of the `ResourcePool<R>` of the unit using the ability
+///
+/// Fractions above 1.0 are allowed, but such abilities can never be afforded.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct PercentCost<R: ResourceType> {
+    pub fraction: f32,
+    _phantom: PhantomData<R>,
+}
+
+impl<R: ResourceType + Into<f32>> PercentCost<R> {
+    pub fn new(fraction: f32) -> Self {
+        Self {
+            fraction,
+            _phantom: PhantomData::default(),
+        }
+    }
+
+    /// The amount of `R` that this cost represents for the provided pool
+    pub fn cost(&self, resource_pool: &ResourcePool<R>) -> R {
+        let cost: R = (resource_pool.max().into() * self.fraction).into();
+        cost.clamp(R::ZERO, R::LOGICAL_MAX)
+    }
+}
+
+/// Abilities which can only be used when the `ResourcePool<R>` of their unit is full, and which empty it,
+/// like an overdrive meter
+///
+/// ```rust
+/// use bevy::ecs::system::CommandQueue;
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+/// use leafwing_abilities::abilities::systems::set_all_abilities_usable;
+/// use leafwing_abilities::abilities::usability::{UsabilityReason, Usable};
+/// use leafwing_abilities::abilities::{Abilities, AbilityState, ActiveAbility};
+/// use leafwing_abilities::resources::doc_resources::Overdrive;
+/// use leafwing_abilities::resources::{check_resource, spend_resource, RequiresFull, ResourcePool};
+///
+/// let mut world = World::new();
+/// let mut command_queue = CommandQueue::default();
+/// let mut commands = Commands::new(&mut command_queue, &world);
+/// let limit_break = commands
+///     .spawn_ability()
+///     .with(RequiresFull::<Overdrive>::default())
+///     .id();
+/// command_queue.apply(&mut world);
+///
+/// let caster = world
+///     .spawn()
+///     .insert(Abilities::from_ability_list(vec![limit_break]))
+///     .insert(ResourcePool::new(Overdrive(99), Overdrive(100), Overdrive(0)))
+///     .id();
+///
+/// let mut check = SystemStage::single(check_resource::<Overdrive>);
+/// let mut reset = SystemStage::single(set_all_abilities_usable);
+/// reset.run(&mut world);
+/// check.run(&mut world);
+/// assert_eq!(
+///     world.get::<Usable>(limit_break).unwrap().reason(),
+///     UsabilityReason::InsufficientResource
+/// );
+///
+/// let mut meter = world.get_mut::<ResourcePool<Overdrive>>(caster).unwrap();
+/// meter.set_current(Overdrive(100));
+/// reset.run(&mut world);
+/// check.run(&mut world);
+/// assert!(world.get::<Usable>(limit_break).unwrap().is_usable());
+///
+/// let mut abilities = world.get_mut::<Abilities>(caster).unwrap();
+/// abilities.active_ability = ActiveAbility::new(limit_break, AbilityState::JustStarted);
+/// SystemStage::single(spend_resource::<Overdrive>).run(&mut world);
+/// assert_eq!(
+///     world.get::<ResourcePool<Overdrive>>(caster).unwrap().current(),
+///     Overdrive(0)
+/// );
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct RequiresFull<R: ResourceType> {
+    _phantom: PhantomData<R>,
+}
+
+impl<R: ResourceType> Default for RequiresFull<R> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData::default(),
+        }
+    }
+}
+
+/// Abilities which can only be used while the `ResourcePool<R>` of their unit holds at least this much,
+/// regardless of how much they cost
+///
+/// ```rust
+/// use bevy::ecs::system::CommandQueue;
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+/// use leafwing_abilities::abilities::systems::set_all_abilities_usable;
+/// use leafwing_abilities::abilities::usability::Usable;
+/// use leafwing_abilities::abilities::Abilities;
+/// use leafwing_abilities::resources::doc_resources::Energy;
+/// use leafwing_abilities::resources::{check_resource, MinResource, ResourcePool};
+///
+/// let mut world = World::new();
+/// let mut command_queue = CommandQueue::default();
+/// let mut commands = Commands::new(&mut command_queue, &world);
+/// let backstab = commands
+///     .spawn_ability()
+///     .with_cost(Energy(10))
+///     .with(MinResource(Energy(50)))
+///     .id();
+/// command_queue.apply(&mut world);
+///
+/// let caster = world
+///     .spawn()
+///     .insert(Abilities::from_ability_list(vec![backstab]))
+///     .insert(ResourcePool::new(Energy(60), Energy(100), Energy(0)))
+///     .id();
+///
+/// let mut reset = SystemStage::single(set_all_abilities_usable);
+/// let mut check = SystemStage::single(check_resource::<Energy>);
+/// reset.run(&mut world);
+/// check.run(&mut world);
+/// assert!(world.get::<Usable>(backstab).unwrap().is_usable());
+///
+/// let mut energy = world.get_mut::<ResourcePool<Energy>>(caster).unwrap();
+/// energy.set_current(Energy(40));
+/// reset.run(&mut world);
+/// check.run(&mut world);
+/// assert!(!world.get::<Usable>(backstab).unwrap().is_usable());
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct MinResource<R: ResourceType>(pub R);
+
+/// One of the ways to pay for an ability with an `AlternateCost`
+///
+/// The resource type is erased, so that options in different resource types can be listed together.
+#[derive(Clone, Copy)]
+pub struct CostOption {
+    resource: TypeId,
+    amount: f32,
+    affordable: fn(&World, Entity, Entity, f32) -> bool,
+}
+
+impl CostOption {
+    pub fn new<R: ResourceType + Into<f32>>(cost: R) -> Self {
+        Self {
+            resource: TypeId::of::<R>(),
+            amount: cost.into(),
+            affordable: can_afford_option::<R>,
+        }
+    }
+
+    /// Whether this option is paid in `R`
+    pub fn is<R: ResourceType>(&self) -> bool {
+        self.resource == TypeId::of::<R>()
+    }
+
+    /// The amount of `R` paid by this option, or `None` if it is paid in another resource type
+    pub fn cost<R: ResourceType>(&self) -> Option<R> {
+        if self.is::<R>() {
+            Some(R::from(self.amount))
+        } else {
+            None
+        }
+    }
+}
+
+impl core::fmt::Debug for CostOption {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CostOption")
+            .field("resource", &self.resource)
+            .field("amount", &self.amount)
+            .finish()
+    }
+}
+
+/// Whether the pool of `R` used by the `caster` can pay `amount`, along with the other costs of the `ability`
+fn can_afford_option<R: ResourceType + Into<f32>>(
+    world: &World,
+    caster: Entity,
+    ability: Entity,
+    amount: f32,
+) -> bool {
+    let owner = ResourcePoolRef::owner_of(caster, world.get::<ResourcePoolRef<R>>(caster));
+    let resource_pool = match world.get::<ResourcePool<R>>(owner) {
+        Some(resource_pool) => resource_pool,
+        None => return false,
+    };
+
+    let other_costs = minimum_cost(
+        resource_pool,
+        world.get::<AbilityCost<R>>(ability),
+        world.get::<PercentCost<R>>(ability),
+        world.get::<OverclockCost<R>>(ability),
+        world.get::<FlexibleCost<R>>(ability),
+    );
+    resource_pool.can_afford(other_costs + R::from(amount))
+}
+
+/// A cost which can be paid in one of several resources, like mana or else life
+///
+/// Options are listed in priority order: the ability is usable if any of them can be afforded,
+/// and only the first affordable one is paid.
+/// Each option is assessed by the `check_resource` of its resource type,
+/// so every resource type listed must be added with `ResourcePoolExt::add_resource_pool`.
+/// Options are assessed on top of the other costs of the ability in the same resource,
+/// matching what `spend_resource` charges.
+///
+/// ```rust
+/// use bevy::ecs::system::CommandQueue;
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::spawning::SpawnAbilityExt;
+/// use leafwing_abilities::abilities::usability::can_use;
+/// use leafwing_abilities::abilities::{Abilities, AbilityState, ActiveAbility};
+/// use leafwing_abilities::resources::doc_resources::{Life, Mana};
+/// use leafwing_abilities::resources::{
+///     check_resource, reset_alternate_costs, spend_resource, AbilityCost, AlternateCost, CostOption,
+///     ResourcePool,
+/// };
+///
+/// let mut world = World::new();
+/// let mut command_queue = CommandQueue::default();
+/// let mut commands = Commands::new(&mut command_queue, &world);
+/// let blood_bolt = commands
+///     .spawn_ability()
+///     .with(AlternateCost::new(vec![
+///         CostOption::new(Mana(20)),
+///         CostOption::new(Life(30)),
+///     ]))
+///     .id();
+/// command_queue.apply(&mut world);
+///
+/// // Out of mana, but not out of life
+/// let caster = world
+///     .spawn()
+///     .insert(Abilities::from_ability_list(vec![blood_bolt]))
+///     .insert(ResourcePool::new(Mana(0), Mana(100), Mana(0)))
+///     .insert(ResourcePool::new(Life(100), Life(100), Life(0)))
+///     .id();
+/// assert!(can_use(&world, caster, blood_bolt));
+///
+/// SystemStage::single(check_resource::<Mana>).run(&mut world);
+/// SystemStage::single(check_resource::<Life>).run(&mut world);
+/// let chosen = *world.get::<AlternateCost>(blood_bolt).unwrap().chosen().unwrap();
+/// assert_eq!(chosen.cost::<Life>(), Some(Life(30)));
+///
+/// world.get_mut::<Abilities>(caster).unwrap().active_ability =
+///     ActiveAbility::new(blood_bolt, AbilityState::JustStarted);
+/// SystemStage::single(spend_resource::<Mana>).run(&mut world);
+/// SystemStage::single(spend_resource::<Life>).run(&mut world);
+/// assert_eq!(world.get::<ResourcePool<Mana>>(caster).unwrap().current(), Mana(0));
+/// assert_eq!(world.get::<ResourcePool<Life>>(caster).unwrap().current(), Life(70));
+///
+/// // Options must be affordable on top of the other costs of the ability in the same resource
+/// world.entity_mut(blood_bolt).insert(AbilityCost(Life(50)));
+/// assert!(!can_use(&world, caster, blood_bolt));
+///
+/// SystemStage::single(reset_alternate_costs).run(&mut world);
+/// SystemStage::single(check_resource::<Mana>).run(&mut world);
+/// SystemStage::single(check_resource::<Life>).run(&mut world);
+/// assert!(world.get::<AlternateCost>(blood_bolt).unwrap().chosen().is_none());
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct AlternateCost {
+    options: Vec<CostOption>,
+    // Whether each option could be afforded, as of the last `check_resource` of its resource type
+    affordable: Vec<bool>,
+}
+
+impl AlternateCost {
+    pub fn new(options: Vec<CostOption>) -> Self {
+        let affordable = vec![false; options.len()];
+        Self {
+            options,
+            affordable,
+        }
+    }
+
+    /// The options of this cost, in priority order
+    pub fn options(&self) -> &[CostOption] {
+        &self.options
+    }
+
+    /// The first option which could be afforded when last checked, which is the one that will be paid
+    pub fn chosen(&self) -> Option<&CostOption> {
+        self.options
+            .iter()
+            .zip(self.affordable.iter())
+            .find(|(_, &affordable)| affordable)
+            .map(|(option, _)| option)
+    }
+
+    /// Whether the `caster` can afford any option of this cost of its `ability` right now
+    pub fn affordable_now(&self, world: &World, caster: Entity, ability: Entity) -> bool {
+        self.options
+            .iter()
+            .any(|option| (option.affordable)(world, caster, ability, option.amount))
+    }
+
+    /// The affordability of each option, updated for those paid in `R`
+    ///
+    /// `other_costs` are the costs of the ability in `R` which are paid alongside the option.
+    fn assessed<R: ResourceType>(
+        &self,
+        resource_pool: &ResourcePool<R>,
+        other_costs: R,
+    ) -> Vec<bool> {
+        self.options
+            .iter()
+            .zip(self.affordable.iter())
+            .map(|(option, &affordable)| match option.cost::<R>() {
+                Some(cost) => resource_pool.can_afford(other_costs + cost),
+                None => affordable,
+            })
+            .collect()
+    }
+}
+
+/// Redirects the costs of this unit's abilities to the `ResourcePool<R>` of another entity,
+/// such as the shared fuel of a vehicle or the energy of a whole squad
+///
+/// Costs, refunds, gains and channeled drains all use the pool of the `owner` instead of the unit's own.
+/// If the owner has no such pool, the unit's abilities are not checked or charged.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ResourcePoolRef<R: ResourceType> {
+    pub owner: Entity,
+    _phantom: PhantomData<R>,
+}
+
+impl<R: ResourceType> ResourcePoolRef<R> {
+    pub fn new(owner: Entity) -> Self {
+        Self {
+            owner,
+            _phantom: PhantomData::default(),
+        }
+    }
+
+    /// The entity whose pool should be used by `unit_entity`
+    pub fn owner_of(unit_entity: Entity, pool_ref: Option<&Self>) -> Entity {
+        pool_ref.map_or(unit_entity, |pool_ref| pool_ref.owner)
+    }
+}
+
+/// Pools are compared using only their `current` values, both to other pools and to bare values of `R`
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ResourcePool<R: ResourceType> {
     current: R,
+    /// The floor of `current`, which is below `R::ZERO` for pools that can go into debt
+    min: R,
+    /// The amount of resource gained per second
+    ///
+    /// Negative values cause the resource to decay, down to `R::ZERO`.
+    /// Units without `InCombat` switch to `out_of_combat_regen_rate` after `out_of_combat_delay`.
     pub regen_rate: R,
+    /// The amount of resource gained per second, once the unit has been out of combat for long enough
+    pub out_of_combat_regen_rate: R,
+    /// The time a unit must spend without `InCombat` before `out_of_combat_regen_rate` applies
+    pub out_of_combat_delay: Duration,
+    /// The time since this unit last had `InCombat`
+    time_out_of_combat: Duration,
+    /// Regeneration stops here, below the maximum, though `current` can still be raised past it directly
+    ///
+    /// `R::LOGICAL_MAX` when there is no soft cap, so that the maximum applies instead.
+    soft_cap: R,
+    /// The amount of resource lost per second while `current` is above the soft cap
+    soft_cap_decay: R,
+    /// Regeneration that was too small to be applied yet, such as fractions of integer resources
+    regen_remainder: f32,
+    /// Scales the regeneration rate based on the `fraction` of the pool that is full
+    ///
+    /// See the `regen_curves` module for some common curves.
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub regen_curve: Option<fn(f32) -> f32>,
+    /// The effective maximum, after all modifiers have been applied
     max: R,
+    /// The maximum before modifiers are applied
+    base_max: R,
+    /// Modifiers to the maximum, such as those granted by buffs or gear
+    ///
+    /// These are not saved: the systems that grant them should add them again after loading.
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    max_modifiers: HashMap<&'static str, MaxModifier<R>>,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     _phantom: PhantomData<R>,
 }
 
+/// A change to the maximum of a `ResourcePool`
+///
+/// All flat bonuses are added to the base maximum before the multipliers are applied.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MaxModifier<R: ResourceType> {
+    pub flat: R,
+    pub multiplier: f32,
+}
+
+/// The values provided to `ResourcePool::try_new` do not describe a valid pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourcePoolError {
+    /// `current` was less than `ResourceType::ZERO`
+    CurrentBelowZero,
+    /// `current` was greater than `max`
+    CurrentAboveMax,
+}
+
+impl core::fmt::Display for ResourcePoolError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ResourcePoolError::CurrentBelowZero => {
+                write!(f, "the current value of the pool is below zero")
+            }
+            ResourcePoolError::CurrentAboveMax => {
+                write!(f, "the current value of the pool is above its maximum")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResourcePoolError {}
+
+/// An empty pool, with no capacity
+impl<R: ResourceType> Default for ResourcePool<R> {
+    fn default() -> Self {
+        Self::new(R::ZERO, R::ZERO, R::ZERO)
+    }
+}
+
 impl<R: ResourceType> ResourcePool<R> {
+    /// Creates a new pool
+    ///
+    /// # Panics
+    ///
+    /// Panics if `current` is below zero or above `max`: use `ResourcePool::try_new` for untrusted values.
     pub fn new(current: R, max: R, regen_rate: R) -> Self {
-        assert!(current >= R::ZERO);
-        assert!(current <= max);
-        Self {
+        Self::try_new(current, max, regen_rate).expect("Invalid resource pool")
+    }
+
+    /// Creates a new pool, or returns an error if `current` is below zero or above `max`
+    ///
+    /// Prefer this to `ResourcePool::new` for values from save files or mods.
+    ///
+    /// ```rust
+    /// use leafwing_abilities::resources::doc_resources::Life;
+    /// use leafwing_abilities::resources::{ResourcePool, ResourcePoolError};
+    ///
+    /// assert!(ResourcePool::try_new(Life(50), Life(100), Life(0)).is_ok());
+    /// assert_eq!(
+    ///     ResourcePool::try_new(Life(-5), Life(100), Life(0)).err(),
+    ///     Some(ResourcePoolError::CurrentBelowZero)
+    /// );
+    /// assert_eq!(
+    ///     ResourcePool::try_new(Life(150), Life(100), Life(0)).err(),
+    ///     Some(ResourcePoolError::CurrentAboveMax)
+    /// );
+    /// ```
+    pub fn try_new(current: R, max: R, regen_rate: R) -> Result<Self, ResourcePoolError> {
+        if current < R::ZERO {
+            return Err(ResourcePoolError::CurrentBelowZero);
+        }
+        if current > max {
+            return Err(ResourcePoolError::CurrentAboveMax);
+        }
+
+        Ok(Self {
             current,
+            min: R::ZERO,
             max,
+            base_max: max,
+            max_modifiers: HashMap::default(),
             regen_rate,
+            out_of_combat_regen_rate: regen_rate,
+            out_of_combat_delay: Duration::ZERO,
+            time_out_of_combat: Duration::ZERO,
+            soft_cap: R::LOGICAL_MAX,
+            soft_cap_decay: R::ZERO,
+            regen_remainder: 0.0,
+            regen_curve: None,
             _phantom: PhantomData::default(),
+        })
+    }
+
+    /// A pool which starts full
+    ///
+    /// ```rust
+    /// use leafwing_abilities::resources::doc_resources::Mana;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let mana = ResourcePool::full(Mana(100), Mana(5));
+    /// assert_eq!(mana.current(), Mana(100));
+    /// assert_eq!(mana.regen_rate, Mana(5));
+    /// ```
+    pub fn full(max: R, regen_rate: R) -> Self {
+        Self::new(max, max, regen_rate)
+    }
+
+    /// A pool which starts empty, such as a rage meter that fills during combat
+    ///
+    /// ```rust
+    /// use leafwing_abilities::resources::doc_resources::Mana;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let rage = ResourcePool::empty(Mana(100), Mana(0));
+    /// assert_eq!(rage.current(), Mana(0));
+    /// assert_eq!(rage.max(), Mana(100));
+    /// ```
+    pub fn empty(max: R, regen_rate: R) -> Self {
+        Self::new(R::ZERO, max, regen_rate)
+    }
+
+    /// A pool which starts full, and never regenerates
+    ///
+    /// ```rust
+    /// use leafwing_abilities::resources::doc_resources::Mana;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let charges = ResourcePool::simple(Mana(3));
+    /// assert_eq!(charges.current(), Mana(3));
+    /// assert_eq!(charges.regen_rate, Mana(0));
+    /// ```
+    pub fn simple(max: R) -> Self {
+        Self::full(max, R::ZERO)
+    }
+
+    /// Regenerates at a different rate once the unit has been out of combat for `delay`
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::resources::doc_resources::Mana;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let mut mana = ResourcePool::new(Mana(0), Mana(100), Mana(2))
+    ///     .with_out_of_combat_regen(Mana(20), Duration::from_secs(5));
+    ///
+    /// mana.tick_combat_timer(true, Duration::from_secs(1));
+    /// assert_eq!(mana.current_regen_rate(), Mana(2));
+    ///
+    /// // Leaving combat only speeds up regeneration after the delay
+    /// mana.tick_combat_timer(false, Duration::from_secs(3));
+    /// assert_eq!(mana.current_regen_rate(), Mana(2));
+    /// mana.tick_combat_timer(false, Duration::from_secs(3));
+    /// assert_eq!(mana.current_regen_rate(), Mana(20));
+    ///
+    /// mana.tick_combat_timer(true, Duration::from_secs(1));
+    /// assert_eq!(mana.current_regen_rate(), Mana(2));
+    /// ```
+    pub fn with_out_of_combat_regen(mut self, regen_rate: R, delay: Duration) -> Self {
+        self.out_of_combat_regen_rate = regen_rate;
+        self.out_of_combat_delay = delay;
+        self
+    }
+
+    /// Scales regeneration by `regen_curve`, based on the `fraction` of the pool that is full
+    pub fn with_regen_curve(mut self, regen_curve: fn(f32) -> f32) -> Self {
+        self.regen_curve = Some(regen_curve);
+        self
+    }
+
+    /// Stops regeneration at `soft_cap`, below the maximum, like shields that can only be overcharged by pickups
+    ///
+    /// Direct gains can still raise the pool above the soft cap,
+    /// after which it decays back down to it by `decay_per_second`, rather than regenerating.
+    /// Pools with `TickRegen` stop regenerating at the soft cap, but do not decay.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::resources::doc_resources::Shields;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let mut shields = ResourcePool::new(Shields(0), Shields(100), Shields(10))
+    ///     .with_soft_cap(Shields(50), Shields(5));
+    ///
+    /// // Regeneration stops at the soft cap
+    /// shields.regenerate(Duration::from_secs(4));
+    /// assert_eq!(shields.current(), Shields(40));
+    /// shields.regenerate(Duration::from_secs(4));
+    /// assert_eq!(shields.current(), Shields(50));
+    ///
+    /// // Overcharged shields slowly decay back to the soft cap
+    /// shields += Shields(30);
+    /// assert_eq!(shields.current(), Shields(80));
+    /// shields.regenerate(Duration::from_secs(2));
+    /// assert_eq!(shields.current(), Shields(70));
+    /// shields.regenerate(Duration::from_secs(10));
+    /// assert_eq!(shields.current(), Shields(50));
+    /// ```
+    pub fn with_soft_cap(mut self, soft_cap: R, decay_per_second: R) -> Self {
+        self.soft_cap = soft_cap;
+        self.soft_cap_decay = decay_per_second;
+        self
+    }
+
+    /// The value at which regeneration stops, which is the maximum unless `with_soft_cap` was used
+    pub fn soft_cap(&self) -> R {
+        self.soft_cap.min(self.max)
+    }
+
+    /// Regenerates by `gain`, without exceeding the soft cap
+    ///
+    /// Negative gains are applied as usual.
+    fn regenerate_up_to_soft_cap(&mut self, gain: R) {
+        let soft_cap = self.soft_cap();
+        if gain > R::ZERO && self.current >= soft_cap {
+            return;
+        }
+
+        *self += gain;
+        if gain > R::ZERO && self.current > soft_cap {
+            self.current = soft_cap;
+        }
+    }
+
+    /// Allows costs to overdraw the pool into debt, down to `floor`
+    ///
+    /// Pools in debt must regenerate back past `R::ZERO` before they have anything to spend,
+    /// and decay never pushes them further into debt.
+    /// This is only meaningful for resource types which can be negative.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::resources::doc_resources::Life;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let mut life = ResourcePool::new(Life(20), Life(100), Life(10)).with_debt_floor(Life(-50));
+    ///
+    /// // Blood magic can be paid for with life the caster does not have
+    /// life -= Life(45);
+    /// assert_eq!(life.current(), Life(-25));
+    /// assert!(life.in_debt());
+    ///
+    /// // The debt is repaid before anything can be spent again
+    /// life.regenerate(Duration::from_secs(2));
+    /// assert_eq!(life.current(), Life(-5));
+    /// life.regenerate(Duration::from_secs(1));
+    /// assert_eq!(life.current(), Life(5));
+    /// assert!(!life.in_debt());
+    ///
+    /// // Debt can never exceed the floor
+    /// life -= Life(200);
+    /// assert_eq!(life.current(), Life(-50));
+    /// ```
+    pub fn with_debt_floor(mut self, floor: R) -> Self {
+        assert!(floor <= R::ZERO);
+        self.min = floor;
+        self
+    }
+
+    /// The lowest value `current` can reach, which is below `R::ZERO` if debt is allowed
+    pub fn min(&self) -> R {
+        self.min
+    }
+
+    /// Whether costs have overdrawn this pool below `R::ZERO`
+    pub fn in_debt(&self) -> bool {
+        self.current < R::ZERO
+    }
+
+    /// Tracks how long the unit has been out of combat, based on whether it currently has `InCombat`
+    pub fn tick_combat_timer(&mut self, in_combat: bool, delta: Duration) {
+        if in_combat {
+            self.time_out_of_combat = Duration::ZERO;
+        } else {
+            self.time_out_of_combat = self.time_out_of_combat.saturating_add(delta);
+        }
+    }
+
+    /// The regeneration rate that currently applies, depending on the time spent out of combat
+    pub fn current_regen_rate(&self) -> R {
+        // Units in combat have spent no time out of it, even if there is no delay
+        if self.time_out_of_combat >= self.out_of_combat_delay
+            && self.time_out_of_combat > Duration::ZERO
+        {
+            self.out_of_combat_regen_rate
+        } else {
+            self.regen_rate
         }
     }
 
@@ -76,61 +989,1192 @@ impl<R: ResourceType> ResourcePool<R> {
     }
 
     pub fn set_current(&mut self, new_value: R) {
-        self.current = new_value.clamp(R::ZERO, self.max);
+        self.current = new_value.clamp(self.min, self.max);
+    }
+
+    /// Adds `amount` to the pool, returning the overflow that could not fit below the maximum
+    ///
+    /// Behaves exactly like `+=`, but reports what was wasted, such as the overheal on a life pool.
+    pub fn add_reporting(&mut self, amount: R) -> R {
+        let total = self.current + amount;
+        *self += amount;
+
+        if total > self.max {
+            total - self.max
+        } else {
+            R::ZERO
+        }
+    }
+
+    /// Subtracts `amount` from the pool, returning the deficit that could not be paid from it
+    ///
+    /// Behaves exactly like `-=`, but reports how far below its `min` the pool would have dropped.
+    pub fn subtract_reporting(&mut self, amount: R) -> R {
+        let headroom = self.current - self.min;
+        let deficit = if amount > headroom {
+            amount - headroom
+        } else {
+            R::ZERO
+        };
+        *self -= amount;
+
+        deficit
+    }
+
+    /// Whether a cost of `cost` could be paid from this pool, including any debt it allows
+    ///
+    /// Matches the check made by `check_resource` for an `AbilityCost<R>`.
+    ///
+    /// ```rust
+    /// use leafwing_abilities::resources::doc_resources::Mana;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let mana = ResourcePool::new(Mana(50), Mana(100), Mana(0));
+    /// assert!(mana.can_afford(Mana(50)));
+    /// assert!(!mana.can_afford(Mana(51)));
+    /// ```
+    pub fn can_afford(&self, cost: R) -> bool {
+        self.current >= cost + self.min
+    }
+
+    /// The value `current` would have after paying `cost`, without spending anything, such as for tooltips
+    ///
+    /// Like `-=`, the result never drops below the `min` of the pool.
+    ///
+    /// ```rust
+    /// use leafwing_abilities::resources::doc_resources::Mana;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let mana = ResourcePool::new(Mana(50), Mana(100), Mana(0));
+    /// assert_eq!(mana.preview_after(Mana(30)), Mana(20));
+    /// assert_eq!(mana.preview_after(Mana(80)), Mana(0));
+    ///
+    /// // Nothing was spent
+    /// assert_eq!(mana.current(), Mana(50));
+    /// ```
+    pub fn preview_after(&self, cost: R) -> R {
+        let headroom = self.current - self.min;
+        if cost > headroom {
+            self.min
+        } else {
+            self.current - cost
+        }
+    }
+
+    /// The maximum before any modifiers are applied
+    pub fn base_max(&self) -> R {
+        self.base_max
     }
 
+    pub fn max_modifier(&self, id: &'static str) -> Option<MaxModifier<R>> {
+        self.max_modifiers.get(id).copied()
+    }
+}
+
+impl<R: ResourceType + Into<f32>> ResourcePool<R> {
+    /// The fraction of the maximum that is currently available, from 0.0 to 1.0
+    ///
+    /// Pools with a maximum of zero are considered empty.
+    ///
+    /// Pools in debt have a negative fraction.
+    pub fn fraction(&self) -> f32 {
+        let max: f32 = self.max.into();
+        if max <= 0.0 {
+            0.0
+        } else {
+            self.current.into() / max
+        }
+    }
+
+    /// The `fraction` of the maximum that is currently available, from 0.0 to 100.0, such as for UI labels
+    pub fn percentage(&self) -> f32 {
+        self.fraction() * 100.0
+    }
+
+    /// Sets `current` to the provided fraction of the maximum, such as to heal to half of it
+    ///
+    /// The fraction is clamped so that `current` stays between `min` and the maximum.
+    ///
+    /// ```rust
+    /// use leafwing_abilities::resources::doc_resources::Life;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let mut life = ResourcePool::new(Life(10), Life(200), Life(0));
+    /// life.set_current_fraction(0.5);
+    /// assert_eq!(life.current(), Life(100));
+    /// assert_eq!(life.fraction(), 0.5);
+    /// assert_eq!(life.percentage(), 50.0);
+    ///
+    /// // Empty pools stay empty
+    /// let mut empty = ResourcePool::new(Life(0), Life(0), Life(0));
+    /// empty.set_current_fraction(0.5);
+    /// assert_eq!(empty.current(), Life(0));
+    /// assert_eq!(empty.percentage(), 0.0);
+    /// ```
+    pub fn set_current_fraction(&mut self, fraction: f32)
+    where
+        R: From<f32>,
+    {
+        let max: f32 = self.max.into();
+        self.set_current((max * fraction).into());
+    }
+
+    /// Applies `delta` worth of regeneration at the current regeneration rate
+    ///
+    /// Regeneration too small to change the value of `R`, such as a fraction of an integer resource,
+    /// is stored until enough has accumulated.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use leafwing_abilities::resources::doc_resources::Rage;
+    /// use leafwing_abilities::resources::ResourcePool;
+    ///
+    /// let mut rage = ResourcePool::new(Rage(0), Rage(100), Rage(1));
+    /// let frame = Duration::from_secs_f64(1.0 / 60.0);
+    ///
+    /// for _ in 0..30 {
+    ///     rage.regenerate(frame);
+    /// }
+    /// assert_eq!(rage.current(), Rage(0));
+    ///
+    /// for _ in 0..31 {
+    ///     rage.regenerate(frame);
+    /// }
+    /// assert_eq!(rage.current(), Rage(1));
+    /// ```
+    pub fn regenerate(&mut self, delta: Duration) {
+        let regen_rate: f32 = self.current_regen_rate().into();
+        self.regenerate_at_rate(regen_rate, delta);
+    }
+
+    /// Applies `delta` worth of regeneration at the provided rate per second, such as one modified by buffs
+    ///
+    /// The `regen_curve` of the pool is still applied.
+    ///
+    /// Pools above their soft cap decay towards it instead.
+    pub fn regenerate_at_rate(&mut self, regen_rate: f32, delta: Duration) {
+        let soft_cap = self.soft_cap();
+        if self.current > soft_cap {
+            let decay_f32: f32 =
+                self.soft_cap_decay.into() * delta.as_secs_f32() + self.regen_remainder;
+            let decay: R = decay_f32.into();
+            self.regen_remainder = decay_f32 - decay.into();
+
+            // Decay never pushes the pool below its soft cap
+            *self -= decay.min(self.current - soft_cap);
+            if self.current <= soft_cap {
+                self.current = soft_cap;
+                self.regen_remainder = 0.0;
+            }
+            return;
+        }
+
+        let multiplier = match self.regen_curve {
+            Some(regen_curve) => regen_curve(self.fraction()),
+            None => 1.0,
+        };
+        let resource_gain_f32: f32 =
+            regen_rate * multiplier * delta.as_secs_f32() + self.regen_remainder;
+        let resource_gain: R = resource_gain_f32.into();
+        self.regen_remainder = resource_gain_f32 - resource_gain.into();
+
+        // Decay can empty the pool, but never pushes it into debt
+        let floor = self.current.min(R::ZERO);
+        self.regenerate_up_to_soft_cap(resource_gain);
+        if self.current == soft_cap {
+            // Regeneration beyond the soft cap is wasted
+            self.regen_remainder = self.regen_remainder.min(0.0);
+        }
+        if self.current < floor {
+            self.current = floor;
+        }
+    }
+
+    /// Sets the maximum before any modifiers are applied
     pub fn set_max(&mut self, new_max: R) {
-        self.max = new_max.clamp(R::ZERO, R::LOGICAL_MAX);
+        self.base_max = new_max.clamp(R::ZERO, R::LOGICAL_MAX);
+        self.recompute_max();
+    }
+
+    /// Adds a modifier to the maximum, replacing any existing modifier with the same `id`
+    pub fn add_max_modifier(&mut self, id: &'static str, flat: R, multiplier: f32) {
+        self.max_modifiers
+            .insert(id, MaxModifier { flat, multiplier });
+        self.recompute_max();
+    }
+
+    /// Removes the modifier with the provided `id`, lowering `current` if it now exceeds the maximum
+    pub fn remove_max_modifier(&mut self, id: &'static str) -> Option<MaxModifier<R>> {
+        let removed = self.max_modifiers.remove(id);
+        self.recompute_max();
+        removed
+    }
+
+    fn recompute_max(&mut self) {
+        let mut flat: f32 = self.base_max.into();
+        let mut multiplier = 1.0;
+        for modifier in self.max_modifiers.values() {
+            flat += modifier.flat.into();
+            multiplier *= modifier.multiplier;
+        }
+
+        let max: R = (flat * multiplier).into();
+        self.max = max.clamp(R::ZERO, R::LOGICAL_MAX);
         if self.current > self.max {
             self.current = self.max
         }
     }
 }
 
+/// Common curves for `ResourcePool::regen_curve`
+///
+/// Each takes the fraction of the pool that is full, and returns a multiplier for its regeneration rate.
+pub mod regen_curves {
+    /// Regenerates twice as fast when empty, slowing linearly to a stop when full
+    ///
+    /// Refilling the whole pool takes the same time as at the base rate.
+    pub fn linear(fraction: f32) -> f32 {
+        2.0 * (1.0 - fraction.clamp(0.0, 1.0))
+    }
+
+    /// Like `linear`, but eases in and out of the fastest and slowest rates
+    pub fn ease(fraction: f32) -> f32 {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let smoothstep = fraction * fraction * (3.0 - 2.0 * fraction);
+        2.0 * (1.0 - smoothstep)
+    }
+
+    /// Regenerates twice as fast below 25%, such as for comeback mechanics
+    pub fn comeback(fraction: f32) -> f32 {
+        if fraction < 0.25 {
+            2.0
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Marker component for units that are in combat, and so regenerate at their `regen_rate`
+///
+/// Insert and remove this from your own systems, such as when dealing or taking damage.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct InCombat;
+
+/// Marker component for units whose resource pools do not regenerate or decay, such as while shapeshifted
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy::utils::Instant;
+/// use core::time::Duration;
+/// use leafwing_abilities::resources::doc_resources::Mana;
+/// use leafwing_abilities::resources::{regen_resource, RegenLocked, ResourcePool};
+///
+/// let mut world = World::new();
+/// let start = Instant::now();
+/// let mut time = Time::default();
+/// time.update_with_instant(start);
+/// world.insert_resource(time);
+///
+/// let caster = world
+///     .spawn()
+///     .insert(ResourcePool::new(Mana(10), Mana(100), Mana(5)))
+///     .insert(RegenLocked)
+///     .id();
+///
+/// let mut stage = SystemStage::single(regen_resource::<Mana>);
+/// for frame in 1..=3 {
+///     let mut time = world.get_resource_mut::<Time>().unwrap();
+///     time.update_with_instant(start + Duration::from_secs(frame));
+///     stage.run(&mut world);
+/// }
+/// assert_eq!(world.get::<ResourcePool<Mana>>(caster).unwrap().current(), Mana(10));
+///
+/// world.entity_mut(caster).remove::<RegenLocked>();
+/// let mut time = world.get_resource_mut::<Time>().unwrap();
+/// time.update_with_instant(start + Duration::from_secs(4));
+/// stage.run(&mut world);
+/// assert_eq!(world.get::<ResourcePool<Mana>>(caster).unwrap().current(), Mana(15));
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct RegenLocked;
+
+/// Temporary changes to the regeneration rate of the `ResourcePool<R>` of a unit, such as buffs
+///
+/// The effective rate is `(base + sum(flat)) * product(multiplier)`,
+/// where `base` is the rate the pool would otherwise regenerate at.
+/// Each modifier expires independently, leaving the base rate untouched.
+///
+/// ```rust
+/// use core::time::Duration;
+/// use leafwing_abilities::resources::doc_resources::Mana;
+/// use leafwing_abilities::resources::RegenModifiers;
+///
+/// let mut modifiers = RegenModifiers::<Mana>::default();
+/// modifiers.add("mana_tide", Mana(6), 1.0, Duration::from_secs(10));
+/// modifiers.add("meditation", Mana(0), 2.0, Duration::from_secs(4));
+/// assert_eq!(modifiers.effective_rate(4.0), 20.0);
+///
+/// modifiers.tick(Duration::from_secs(5));
+/// assert_eq!(modifiers.effective_rate(4.0), 10.0);
+/// ```
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct RegenModifiers<R: ResourceType> {
+    modifiers: Vec<RegenModifier<R>>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct RegenModifier<R: ResourceType> {
+    id: &'static str,
+    flat: R,
+    multiplier: f32,
+    timer: Timer,
+}
+
+impl<R: ResourceType> Default for RegenModifiers<R> {
+    fn default() -> Self {
+        Self {
+            modifiers: Vec::default(),
+        }
+    }
+}
+
+impl<R: ResourceType + Into<f32>> RegenModifiers<R> {
+    /// Adds a modifier which lasts for `duration`, replacing any existing modifier with the same `id`
+    pub fn add(&mut self, id: &'static str, flat: R, multiplier: f32, duration: Duration) {
+        self.remove(id);
+        self.modifiers.push(RegenModifier {
+            id,
+            flat,
+            multiplier,
+            timer: Timer::new(duration, false),
+        });
+    }
+
+    pub fn remove(&mut self, id: &'static str) {
+        self.modifiers.retain(|modifier| modifier.id != id);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modifiers.is_empty()
+    }
+
+    /// Advances every modifier, removing those which have expired
+    pub fn tick(&mut self, delta: Duration) {
+        for modifier in self.modifiers.iter_mut() {
+            modifier.timer.tick(delta);
+        }
+        self.modifiers.retain(|modifier| !modifier.timer.finished());
+    }
+
+    /// The regeneration rate per second, once every modifier has been applied to `base_rate`
+    pub fn effective_rate(&self, base_rate: f32) -> f32 {
+        let mut flat = base_rate;
+        let mut multiplier = 1.0;
+        for modifier in self.modifiers.iter() {
+            flat += modifier.flat.into();
+            multiplier *= modifier.multiplier;
+        }
+        flat * multiplier
+    }
+}
+
+/// Removes expired `RegenModifiers`
+pub fn tick_regen_modifiers<R: ResourceType + Into<f32>>(
+    mut query: Query<&mut RegenModifiers<R>>,
+    time: Res<Time>,
+    timestep: Option<Res<AbilityTimestep>>,
+) {
+    let delta = ability_delta(&time, timestep);
+    for mut regen_modifiers in query.iter_mut() {
+        // Extra check here avoids change-detection false positives
+        if !regen_modifiers.is_empty() {
+            regen_modifiers.tick(delta);
+        }
+    }
+}
+
+/// Regenerates every `ResourcePool<R>` continuously, scaled by any `LocalTimeScale` of its unit
+///
+/// Negative regeneration rates drain the pool instead, stopping at its floor.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy::utils::Instant;
+/// use core::time::Duration;
+/// use leafwing_abilities::resources::doc_resources::Rage;
+/// use leafwing_abilities::resources::{regen_resource, ResourcePool};
+///
+/// let mut world = World::new();
+/// let start = Instant::now();
+/// let mut time = Time::default();
+/// time.update_with_instant(start);
+/// world.insert_resource(time);
+///
+/// let berserker = world
+///     .spawn()
+///     .insert(ResourcePool::new(Rage(80), Rage(100), Rage(-20)))
+///     .id();
+///
+/// let mut stage = SystemStage::single(regen_resource::<Rage>);
+/// for (seconds, expected) in [(1, 60), (2, 40), (3, 20), (4, 0), (10, 0)] {
+///     let mut time = world.get_resource_mut::<Time>().unwrap();
+///     time.update_with_instant(start + Duration::from_secs(seconds));
+///     stage.run(&mut world);
+///
+///     let rage = world.get::<ResourcePool<Rage>>(berserker).unwrap();
+///     assert_eq!(rage.current(), Rage(expected));
+/// }
+/// ```
 pub fn regen_resource<R: ResourceType + From<f32> + Into<f32>>(
-    mut query: Query<&mut ResourcePool<R>>,
+    mut query: Query<
+        (
+            &mut ResourcePool<R>,
+            Option<&InCombat>,
+            Option<&RegenModifiers<R>>,
+            Option<&LocalTimeScale>,
+        ),
+        (Without<RegenLocked>, Without<TickRegen<R>>),
+    >,
     time: Res<Time>,
+    timestep: Option<Res<AbilityTimestep>>,
 ) {
-    for mut resource_pool in query.iter_mut() {
-        let resource_gain_f32: f32 = resource_pool.regen_rate.into() * time.delta_seconds();
-        let resource_gain: R = resource_gain_f32.into();
-        *resource_pool += resource_gain;
+    let delta = ability_delta(&time, timestep);
+    for (mut resource_pool, in_combat, regen_modifiers, local_time_scale) in query.iter_mut() {
+        let delta = LocalTimeScale::scale(local_time_scale, delta);
+        let regen_modifiers = regen_modifiers.filter(|regen_modifiers| !regen_modifiers.is_empty());
+
+        // Fast path: pools which never regenerate are skipped without being marked as changed
+        if resource_pool.regen_rate == R::ZERO
+            && resource_pool.out_of_combat_regen_rate == R::ZERO
+            && regen_modifiers.is_none()
+            && resource_pool.current() <= resource_pool.soft_cap()
+        {
+            continue;
+        }
+
+        resource_pool.tick_combat_timer(in_combat.is_some(), delta);
+
+        let base_rate: f32 = resource_pool.current_regen_rate().into();
+        let regen_rate = match regen_modifiers {
+            Some(regen_modifiers) => regen_modifiers.effective_rate(base_rate),
+            None => base_rate,
+        };
+        resource_pool.regenerate_at_rate(regen_rate, delta);
     }
 }
 
-pub fn tick_regen_resource<R: ResourceType>(mut query: Query<&mut ResourcePool<R>>) {
-    for mut resource_pool in query.iter_mut() {
-        let delta_resource = resource_pool.regen_rate;
-        *resource_pool += delta_resource;
+/// Regenerates the `ResourcePool<R>` of this unit in whole steps, once per `interval`,
+/// rather than continuously
+///
+/// Each step adds the `current_regen_rate` of the pool, which is then an amount per interval rather than per second.
+/// This suits integer resources like combo points or arrows, which cannot gain fractions of a unit.
+/// Pools with this component are ignored by `regen_resource`.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use bevy::utils::Instant;
+/// use core::time::Duration;
+/// use leafwing_abilities::resources::doc_resources::ComboPoints;
+/// use leafwing_abilities::resources::{tick_regen_resource, ResourcePool, TickRegen};
+///
+/// let mut world = World::new();
+/// let start = Instant::now();
+/// let mut time = Time::default();
+/// time.update_with_instant(start);
+/// world.insert_resource(time);
+///
+/// let rogue = world
+///     .spawn()
+///     .insert(ResourcePool::empty(ComboPoints(5), ComboPoints(1)))
+///     .insert(TickRegen::<ComboPoints>::new(Duration::from_secs(2)))
+///     .id();
+///
+/// let mut stage = SystemStage::single(tick_regen_resource::<ComboPoints>);
+/// for (seconds, expected) in [(1, 0), (2, 1), (3, 1), (7, 3)] {
+///     let mut time = world.get_resource_mut::<Time>().unwrap();
+///     time.update_with_instant(start + Duration::from_secs(seconds));
+///     stage.run(&mut world);
+///     let combo_points = world.get::<ResourcePool<ComboPoints>>(rogue).unwrap();
+///     assert_eq!(combo_points.current(), ComboPoints(expected));
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct TickRegen<R: ResourceType> {
+    interval: Timer,
+    _phantom: PhantomData<R>,
+}
+
+impl<R: ResourceType> TickRegen<R> {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval: Timer::new(interval, true),
+            _phantom: PhantomData::default(),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval.duration()
     }
 }
 
-pub fn check_resource<R: ResourceType>(
-    unit_query: Query<(&Abilities, &ResourcePool<R>)>,
-    mut ability_query: Query<(&R, &mut Usable), With<Ability>>,
+/// Regenerates pools with `TickRegen` by whole steps of their regeneration rate, scaled by any `LocalTimeScale`
+pub fn tick_regen_resource<R: ResourceType>(
+    mut query: Query<
+        (
+            &mut ResourcePool<R>,
+            &mut TickRegen<R>,
+            Option<&InCombat>,
+            Option<&LocalTimeScale>,
+        ),
+        Without<RegenLocked>,
+    >,
+    time: Res<Time>,
+    timestep: Option<Res<AbilityTimestep>>,
 ) {
-    for (abilities, &resource_pool) in unit_query.iter() {
+    let delta = ability_delta(&time, timestep);
+    for (mut resource_pool, mut tick_regen, in_combat, local_time_scale) in query.iter_mut() {
+        let delta = LocalTimeScale::scale(local_time_scale, delta);
+        resource_pool.tick_combat_timer(in_combat.is_some(), delta);
+
+        let steps = tick_regen.interval.tick(delta).times_finished();
+        let regen_rate = resource_pool.current_regen_rate();
+        for _ in 0..steps {
+            resource_pool.regenerate_up_to_soft_cap(regen_rate);
+        }
+    }
+}
+
+/// Marks abilities as unusable when their caster cannot afford their costs in `R`
+///
+/// Abilities without costs in `R` are unaffected.
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::{Abilities, Ability};
+/// use leafwing_abilities::resources::doc_resources::Mana;
+/// use leafwing_abilities::resources::{check_resource, AbilityCost, ResourcePool};
+///
+/// let mut world = World::new();
+/// // A mix of abilities with and without mana costs, and one which no longer exists
+/// let fireball = world.spawn().insert(Ability).insert(AbilityCost(Mana(50))).id();
+/// let punch = world.spawn().insert(Ability).id();
+/// let despawned = Entity::from_raw(999);
+///
+/// let abilities = Abilities::from_ability_list(vec![fireball, punch, despawned]);
+/// world
+///     .spawn()
+///     .insert(abilities)
+///     .insert(ResourcePool::new(Mana(10), Mana(100), Mana(0)));
+///
+/// // Abilities which cannot be checked are skipped, rather than panicking
+/// SystemStage::single(check_resource::<Mana>).run(&mut world);
+/// ```
+pub fn check_resource<R: ResourceType + Into<f32>>(
+    unit_query: Query<(Entity, &Abilities, Option<&ResourcePoolRef<R>>)>,
+    pool_query: Query<&ResourcePool<R>>,
+    mut ability_query: Query<
+        (
+            Option<&AbilityCost<R>>,
+            Option<&PercentCost<R>>,
+            Option<&OverclockCost<R>>,
+            Option<&FlexibleCost<R>>,
+            Option<&RequiresFull<R>>,
+            Option<&MinResource<R>>,
+            Option<&mut AlternateCost>,
+            &mut Usable,
+        ),
+        With<Ability>,
+    >,
+) {
+    for (unit_entity, abilities, pool_ref) in unit_query.iter() {
+        // Units without a pool of this resource have nothing to check against
+        let resource_pool = match pool_query.get(ResourcePoolRef::owner_of(unit_entity, pool_ref)) {
+            Ok(resource_pool) => resource_pool,
+            Err(_) => continue,
+        };
+
         for ability_entity in abilities.ability_list() {
-            let (&resource_cost, mut usable) = ability_query.get_mut(ability_entity).unwrap();
+            // Abilities without a `Usable` component cannot be disabled
+            let (
+                flat_cost,
+                percent_cost,
+                overclock_cost,
+                flexible_cost,
+                requires_full,
+                min_resource,
+                alternate_cost,
+                mut usable,
+            ) = match ability_query.get_mut(ability_entity) {
+                Ok(components) => components,
+                Err(_) => continue,
+            };
             // Failing to have enough resources of one type can disable an ability,
             // but the converse is not true! An ability may be unusable for other reasons!
-            if resource_pool < resource_cost {
-                *usable = Usable(false);
+            if !can_afford_ability(
+                resource_pool,
+                flat_cost,
+                percent_cost,
+                overclock_cost,
+                flexible_cost,
+                requires_full,
+                min_resource,
+            ) {
+                usable.block(UsabilityReason::InsufficientResource);
+            }
+
+            // Whether any option of an `AlternateCost` is affordable is decided by `check_alternate_costs`
+            if let Some(mut alternate_cost) = alternate_cost {
+                let other_costs = minimum_cost(
+                    resource_pool,
+                    flat_cost,
+                    percent_cost,
+                    overclock_cost,
+                    flexible_cost,
+                );
+                let affordable = alternate_cost.assessed(resource_pool, other_costs);
+                // Extra check here avoids change-detection false positives
+                if alternate_cost.affordable != affordable {
+                    alternate_cost.affordable = affordable;
+                }
+            }
+        }
+    }
+}
+
+/// Marks every option of each `AlternateCost` as unaffordable, before they are assessed again by `check_resource`
+///
+/// Options in resource types which the caster has no pool of are never assessed, and so stay unaffordable.
+pub fn reset_alternate_costs(mut query: Query<&mut AlternateCost>) {
+    for mut alternate_cost in query.iter_mut() {
+        // Extra check here avoids change-detection false positives
+        if alternate_cost.affordable.contains(&true) {
+            for affordable in alternate_cost.affordable.iter_mut() {
+                *affordable = false;
+            }
+        }
+    }
+}
+
+/// Marks abilities with an `AlternateCost` as unusable when their caster cannot afford any of its options
+///
+/// The options are assessed by the `check_resource` of each resource type,
+/// so this operates on the unit's `Abilities` directly, once those have been collected.
+pub fn check_alternate_costs(
+    mut unit_query: Query<&mut Abilities>,
+    ability_query: Query<&AlternateCost, With<Ability>>,
+) {
+    for mut abilities in unit_query.iter_mut() {
+        for ability_entity in abilities.ability_list() {
+            if let Ok(alternate_cost) = ability_query.get(ability_entity) {
+                if alternate_cost.chosen().is_none() {
+                    abilities.block_usable(ability_entity, UsabilityReason::InsufficientResource);
+                }
             }
         }
     }
 }
 
-pub fn spend_resource<R: ResourceType>(
-    mut unit_query: Query<(&Abilities, &mut ResourcePool<R>)>,
-    ability_query: Query<&R, With<Ability>>,
+/// The `GateCheck` matching `check_alternate_costs`
+pub fn alternate_cost_gate(
+    world: &World,
+    caster: Entity,
+    ability: Entity,
+) -> Option<UsabilityReason> {
+    let alternate_cost = world.get::<AlternateCost>(ability)?;
+
+    if alternate_cost.affordable_now(world, caster, ability) {
+        None
+    } else {
+        Some(UsabilityReason::InsufficientResource)
+    }
+}
+
+/// The `GateCheck` matching `check_resource::<R>`, registered by `ResourcePoolExt::add_resource_pool`
+pub fn resource_gate<R: ResourceType + Into<f32>>(
+    world: &World,
+    caster: Entity,
+    ability: Entity,
+) -> Option<UsabilityReason> {
+    let owner = ResourcePoolRef::owner_of(caster, world.get::<ResourcePoolRef<R>>(caster));
+    let resource_pool = world.get::<ResourcePool<R>>(owner)?;
+
+    let affordable = can_afford_ability(
+        resource_pool,
+        world.get::<AbilityCost<R>>(ability),
+        world.get::<PercentCost<R>>(ability),
+        world.get::<OverclockCost<R>>(ability),
+        world.get::<FlexibleCost<R>>(ability),
+        world.get::<RequiresFull<R>>(ability),
+        world.get::<MinResource<R>>(ability),
+    );
+    if affordable {
+        None
+    } else {
+        Some(UsabilityReason::InsufficientResource)
+    }
+}
+
+/// Whether every requirement in `R` of an ability is met by the provided pool
+fn can_afford_ability<R: ResourceType + Into<f32>>(
+    resource_pool: &ResourcePool<R>,
+    flat_cost: Option<&AbilityCost<R>>,
+    percent_cost: Option<&PercentCost<R>>,
+    overclock_cost: Option<&OverclockCost<R>>,
+    flexible_cost: Option<&FlexibleCost<R>>,
+    requires_full: Option<&RequiresFull<R>>,
+    min_resource: Option<&MinResource<R>>,
+) -> bool {
+    let resource_cost = minimum_cost(
+        resource_pool,
+        flat_cost,
+        percent_cost,
+        overclock_cost,
+        flexible_cost,
+    );
+
+    // Pools which allow debt can pay costs larger than what they have
+    if *resource_pool < resource_cost + resource_pool.min() {
+        return false;
+    }
+
+    if requires_full.is_some() && resource_pool.current() < resource_pool.max() {
+        return false;
+    }
+
+    match min_resource {
+        Some(&MinResource(min_resource)) => resource_pool.current() >= min_resource,
+        None => true,
+    }
+}
+
+/// The amount of the resource `R` that the caster gains whenever an ability is used
+///
+/// ```rust
+/// use bevy::prelude::*;
+/// use leafwing_abilities::abilities::{Abilities, Ability, AbilityState, ActiveAbility};
+/// use leafwing_abilities::resources::doc_resources::Mana;
+/// use leafwing_abilities::resources::{gain_resource_on_use, ResourceGainOnUse, ResourcePool};
+///
+/// let mut world = World::new();
+/// let siphon = world.spawn().insert(Ability).insert(ResourceGainOnUse(Mana(10))).id();
+///
+/// let mut abilities = Abilities::from_ability_list(vec![siphon]);
+/// abilities.active_ability = ActiveAbility::new(siphon, AbilityState::JustStarted);
+/// let caster = world
+///     .spawn()
+///     .insert(abilities)
+///     .insert(ResourcePool::new(Mana(50), Mana(65), Mana(0)))
+///     .id();
+///
+/// let mut stage = SystemStage::single(gain_resource_on_use::<Mana>);
+/// stage.run(&mut world);
+/// assert_eq!(world.get::<ResourcePool<Mana>>(caster).unwrap().current(), Mana(60));
+///
+/// // Gains cannot exceed the maximum of the pool
+/// stage.run(&mut world);
+/// assert_eq!(world.get::<ResourcePool<Mana>>(caster).unwrap().current(), Mana(65));
+/// ```
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct ResourceGainOnUse<R: ResourceType>(pub R);
+
+/// Credits `ResourceGainOnUse` to the pool of the caster of each ability that was just used
+pub fn gain_resource_on_use<R: ResourceType>(
+    unit_query: Query<(Entity, &Abilities, Option<&ResourcePoolRef<R>>)>,
+    mut pool_query: Query<&mut ResourcePool<R>>,
+    ability_query: Query<&ResourceGainOnUse<R>, With<Ability>>,
 ) {
-    for (abilities, mut resource_pool) in unit_query.iter_mut() {
-        if abilities.active_ability.state == AbilityState::JustStarted {
-            let active_ability_entity = abilities.active_ability.entity.unwrap();
-            let &resource_cost = ability_query.get(active_ability_entity).unwrap();
+    for (unit_entity, abilities, pool_ref) in unit_query.iter() {
+        let mut resource_pool =
+            match pool_query.get_mut(ResourcePoolRef::owner_of(unit_entity, pool_ref)) {
+                Ok(resource_pool) => resource_pool,
+                Err(_) => continue,
+            };
 
-            *resource_pool -= resource_cost;
+        for active_ability in abilities.active_abilities() {
+            if active_ability.state != AbilityState::JustStarted {
+                continue;
+            }
+
+            if let Some(&ResourceGainOnUse(gain)) = active_ability
+                .entity
+                .and_then(|ability_entity| ability_query.get(ability_entity).ok())
+            {
+                *resource_pool += gain;
+            }
+        }
+    }
+}
+
+pub fn spend_resource<R: ResourceType + Into<f32>>(
+    mut commands: Commands,
+    unit_query: Query<(Entity, &Abilities, Option<&ResourcePoolRef<R>>)>,
+    mut pool_query: Query<&mut ResourcePool<R>>,
+    ability_query: Query<
+        (
+            Option<&AbilityCost<R>>,
+            Option<&PercentCost<R>>,
+            Option<&OverclockCost<R>>,
+            Option<&FlexibleCost<R>>,
+            Option<&RequiresFull<R>>,
+            Option<&AlternateCost>,
+        ),
+        With<Ability>,
+    >,
+    #[cfg(feature = "telemetry")] mut stats_query: Query<
+        &mut crate::abilities::telemetry::AbilityStats,
+        With<Ability>,
+    >,
+) {
+    for (unit_entity, abilities, pool_ref) in unit_query.iter() {
+        let mut resource_pool =
+            match pool_query.get_mut(ResourcePoolRef::owner_of(unit_entity, pool_ref)) {
+                Ok(resource_pool) => resource_pool,
+                Err(_) => continue,
+            };
+
+        for active_ability in abilities.active_abilities() {
+            if active_ability.state == AbilityState::JustStarted {
+                let active_ability_entity = match active_ability.entity {
+                    Some(active_ability_entity) => active_ability_entity,
+                    None => continue,
+                };
+                let (
+                    flat_cost,
+                    percent_cost,
+                    overclock_cost,
+                    flexible_cost,
+                    requires_full,
+                    alternate_cost,
+                ) = match ability_query.get(active_ability_entity) {
+                    Ok(costs) => costs,
+                    Err(_) => continue,
+                };
+                let mut resource_cost = total_cost(flat_cost, percent_cost, &*resource_pool);
+
+                // Only the chosen option of an `AlternateCost` is paid, by the pool of its resource type
+                if let Some(alternate) = alternate_cost
+                    .and_then(AlternateCost::chosen)
+                    .and_then(CostOption::cost::<R>)
+                {
+                    resource_cost = resource_cost + alternate;
+                }
+                // Whatever remains once the costs so far have been paid
+                let available = |resource_cost: R| {
+                    if resource_pool.current() > resource_cost {
+                        resource_pool.current() - resource_cost
+                    } else {
+                        R::ZERO
+                    }
+                };
+
+                if let Some(overclock_cost) = overclock_cost {
+                    let (surplus, reduction) = overclock_cost.overclock(available(resource_cost));
+                    resource_cost = resource_cost + overclock_cost.base + surplus;
+
+                    if reduction > Duration::ZERO {
+                        commands
+                            .entity(active_ability_entity)
+                            .insert(OverclockReduction(reduction));
+                    }
+                }
+
+                if let Some(flexible_cost) = flexible_cost {
+                    resource_cost = resource_cost + available(resource_cost).min(flexible_cost.max);
+                }
+
+                // Abilities which require a full pool consume all of it
+                if requires_full.is_some() {
+                    resource_cost = resource_cost.max(resource_pool.current());
+                }
+
+                *resource_pool -= resource_cost;
+                #[cfg(feature = "telemetry")]
+                if let Ok(mut stats) = stats_query.get_mut(active_ability_entity) {
+                    stats.record_spend(resource_cost.into());
+                }
+                commands
+                    .entity(active_ability_entity)
+                    .insert(LastSpent(resource_cost));
+            }
+        }
+    }
+}
+
+/// The amount of `R` paid the last time this ability was used
+///
+/// Inserted on the ability entity by `spend_resource`, and used to refund cancelled abilities.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct LastSpent<R: ResourceType>(pub R);
+
+/// Returns the resources spent on abilities that were cancelled by an `AbilityCancelled` event
+pub fn refund_resource<R: ResourceType>(
+    mut event_reader: EventReader<AbilityCancelled>,
+    pool_ref_query: Query<&ResourcePoolRef<R>>,
+    mut pool_query: Query<&mut ResourcePool<R>>,
+    mut ability_query: Query<&mut LastSpent<R>, With<Ability>>,
+) {
+    for event in event_reader.iter() {
+        // Abilities do not pay their costs until their cast completes
+        if event.state == AbilityState::Casting {
+            continue;
+        }
+
+        // Refunds return to the pool that paid the cost
+        let owner = ResourcePoolRef::owner_of(event.caster, pool_ref_query.get(event.caster).ok());
+        if let (Ok(mut resource_pool), Ok(mut last_spent)) = (
+            pool_query.get_mut(owner),
+            ability_query.get_mut(event.ability),
+        ) {
+            // Each payment can only be refunded once
+            let refund = core::mem::replace(&mut last_spent.0, R::ZERO);
+            *resource_pool += refund;
+        }
+    }
+}
+
+/// Abilities which continuously drain a resource while they are active, like beams or flamethrowers
+///
+/// Any `AbilityCost<R>` is still paid once when the ability starts.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct Channeled<R: ResourceType> {
+    pub drain_per_second: R,
+}
+
+impl<R: ResourceType + Into<f32>> Channeled<R> {
+    /// The amount of `R` drained over `delta_seconds`
+    pub fn drain(&self, delta_seconds: f32) -> R {
+        let drain_f32: f32 = self.drain_per_second.into() * delta_seconds;
+        drain_f32.into()
+    }
+}
+
+/// Drains the resources of units that are channeling an ability,
+/// stopping the ability once its drain can no longer be afforded
+///
+/// Toggled abilities that are switched on are drained in the same way,
+/// and are switched off once their drain can no longer be afforded.
+/// Passive abilities are drained whenever their drain can be afforded.
+pub fn drain_channeled_resource<R: ResourceType + Into<f32>>(
+    mut unit_query: Query<(Entity, &mut Abilities, Option<&ResourcePoolRef<R>>)>,
+    mut pool_query: Query<&mut ResourcePool<R>>,
+    ability_query: Query<&Channeled<R>, With<Ability>>,
+    passive_query: Query<&Channeled<R>, (With<Ability>, With<Passive>)>,
+    time: Res<Time>,
+    timestep: Option<Res<AbilityTimestep>>,
+) {
+    let delta_seconds = ability_delta(&time, timestep).as_secs_f32();
+    for (unit_entity, mut abilities, pool_ref) in unit_query.iter_mut() {
+        let mut resource_pool =
+            match pool_query.get_mut(ResourcePoolRef::owner_of(unit_entity, pool_ref)) {
+                Ok(resource_pool) => resource_pool,
+                Err(_) => continue,
+            };
+
+        for ability_entity in abilities.ability_list() {
+            if let Ok(channeled) = passive_query.get(ability_entity) {
+                let drain = channeled.drain(delta_seconds);
+
+                if *resource_pool >= drain {
+                    *resource_pool -= drain;
+                }
+            }
+        }
+
+        for ability_entity in abilities.toggled_on() {
+            if let Ok(channeled) = ability_query.get(ability_entity) {
+                let drain = channeled.drain(delta_seconds);
+
+                if *resource_pool < drain {
+                    abilities.toggle(ability_entity);
+                } else {
+                    *resource_pool -= drain;
+                }
+            }
+        }
+
+        for active_ability in abilities.active_abilities_mut() {
+            if active_ability.state != AbilityState::Active {
+                continue;
+            }
+
+            if let Some(ability_entity) = active_ability.entity {
+                if let Ok(channeled) = ability_query.get(ability_entity) {
+                    let drain = channeled.drain(delta_seconds);
+
+                    if *resource_pool < drain {
+                        *active_ability = ActiveAbility::NONE;
+                    } else {
+                        *resource_pool -= drain;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The least that using an ability can cost in `R`, before any surplus is spent
+fn minimum_cost<R: ResourceType + Into<f32>>(
+    resource_pool: &ResourcePool<R>,
+    flat_cost: Option<&AbilityCost<R>>,
+    percent_cost: Option<&PercentCost<R>>,
+    overclock_cost: Option<&OverclockCost<R>>,
+    flexible_cost: Option<&FlexibleCost<R>>,
+) -> R {
+    let overclock_base = overclock_cost.map(|cost| cost.base).unwrap_or(R::ZERO);
+    let flexible_min = flexible_cost.map(|cost| cost.min).unwrap_or(R::ZERO);
+
+    total_cost(flat_cost, percent_cost, resource_pool) + overclock_base + flexible_min
+}
+
+/// The total amount of `R` needed to use an ability, given the pool it is paid from
+///
+/// If an ability has both an `AbilityCost<R>` and a `PercentCost<R>`, their costs are summed.
+fn total_cost<R: ResourceType + Into<f32>>(
+    flat_cost: Option<&AbilityCost<R>>,
+    percent_cost: Option<&PercentCost<R>>,
+    resource_pool: &ResourcePool<R>,
+) -> R {
+    let flat_cost = flat_cost.map(|cost| cost.0).unwrap_or(R::ZERO);
+    let percent_cost = percent_cost
+        .map(|cost| cost.cost(resource_pool))
+        .unwrap_or(R::ZERO);
+
+    flat_cost + percent_cost
+}
+
+/// Sent when the resource pool of an entity is reduced to zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResourceEmptied {
+    pub entity: Entity,
+    /// The `TypeId` of the resource type `R` of the `ResourcePool<R>` that was emptied
+    pub pool: TypeId,
+}
+
+/// Sent when the resource pool of an entity reaches its maximum
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResourceFilled {
+    pub entity: Entity,
+    /// The `TypeId` of the resource type `R` of the `ResourcePool<R>` that was filled
+    pub pool: TypeId,
+}
+
+/// Sends `ResourceEmptied` and `ResourceFilled` events
+/// on the frame that a resource pool crosses the corresponding threshold
+///
+/// ```rust
+/// use bevy::app::Events;
+/// use bevy::prelude::*;
+/// use leafwing_abilities::resources::doc_resources::Mana;
+/// use leafwing_abilities::resources::{
+///     detect_resource_thresholds, ResourceEmptied, ResourceFilled, ResourcePool,
+/// };
+///
+/// let mut world = World::new();
+/// world.insert_resource(Events::<ResourceEmptied>::default());
+/// world.insert_resource(Events::<ResourceFilled>::default());
+/// let mut emptied_reader = world.get_resource::<Events<ResourceEmptied>>().unwrap().get_reader();
+/// let mut filled_reader = world.get_resource::<Events<ResourceFilled>>().unwrap().get_reader();
+///
+/// let mut stage = SystemStage::single(detect_resource_thresholds::<Mana>);
+/// let mut frame = |world: &mut World| {
+///     stage.run(world);
+///     let emptied = world.get_resource::<Events<ResourceEmptied>>().unwrap();
+///     let filled = world.get_resource::<Events<ResourceFilled>>().unwrap();
+///     (emptied_reader.iter(emptied).count(), filled_reader.iter(filled).count())
+/// };
+///
+/// let mage = world.spawn().insert(ResourcePool::new(Mana(30), Mana(100), Mana(0))).id();
+/// assert_eq!(frame(&mut world), (0, 0));
+///
+/// // Draining the pool over several frames empties it exactly once
+/// for expected in [(0, 0), (0, 0), (1, 0)] {
+///     *world.get_mut::<ResourcePool<Mana>>(mage).unwrap() -= Mana(10);
+///     assert_eq!(frame(&mut world), expected);
+/// }
+/// // Draining an empty pool does not empty it again
+/// *world.get_mut::<ResourcePool<Mana>>(mage).unwrap() -= Mana(10);
+/// assert_eq!(frame(&mut world), (0, 0));
+/// world.get_mut::<ResourcePool<Mana>>(mage).unwrap().set_current(Mana(0));
+/// assert_eq!(frame(&mut world), (0, 0));
+///
+/// // Refilling it fills it exactly once
+/// world.get_mut::<ResourcePool<Mana>>(mage).unwrap().set_current(Mana(100));
+/// assert_eq!(frame(&mut world), (0, 1));
+/// world.get_mut::<ResourcePool<Mana>>(mage).unwrap().set_current(Mana(100));
+/// assert_eq!(frame(&mut world), (0, 0));
+///
+/// // Pools which are already empty when first seen have not crossed the threshold
+/// world.spawn().insert(ResourcePool::new(Mana(0), Mana(100), Mana(0)));
+/// assert_eq!(frame(&mut world), (0, 0));
+/// ```
+pub fn detect_resource_thresholds<R: ResourceType>(
+    query: Query<(Entity, &ResourcePool<R>), Changed<ResourcePool<R>>>,
+    removed: RemovedComponents<ResourcePool<R>>,
+    mut previous_values: Local<HashMap<Entity, R>>,
+    mut emptied_writer: EventWriter<ResourceEmptied>,
+    mut filled_writer: EventWriter<ResourceFilled>,
+) {
+    for entity in removed.iter() {
+        previous_values.remove(&entity);
+    }
+
+    for (entity, resource_pool) in query.iter() {
+        let current = resource_pool.current();
+        // Newly observed pools have not crossed any threshold yet
+        if let Some(previous) = previous_values.insert(entity, current) {
+            if previous > R::ZERO && current <= R::ZERO {
+                emptied_writer.send(ResourceEmptied {
+                    entity,
+                    pool: TypeId::of::<R>(),
+                });
+            }
+
+            if previous < resource_pool.max() && current >= resource_pool.max() {
+                filled_writer.send(ResourceFilled {
+                    entity,
+                    pool: TypeId::of::<R>(),
+                });
+            }
+        }
+    }
+}
+
+/// The fraction of the maximum of a `ResourcePool<R>` below which units are marked with `M`
+///
+/// Added by `ResourcePoolExt::add_resource_threshold`.
+pub struct ResourceThreshold<R: ResourceType, M: Component> {
+    pub fraction: f32,
+    _phantom: PhantomData<(R, M)>,
+}
+
+impl<R: ResourceType, M: Component> ResourceThreshold<R, M> {
+    pub fn new(fraction: f32) -> Self {
+        Self {
+            fraction,
+            _phantom: PhantomData::default(),
+        }
+    }
+}
+
+/// Inserts or removes the marker component `M` as resource pools cross their `ResourceThreshold`
+pub fn apply_threshold_marker<R: ResourceType + Into<f32>, M: Component + Default>(
+    mut commands: Commands,
+    threshold: Res<ResourceThreshold<R, M>>,
+    query: Query<(Entity, &ResourcePool<R>, Option<&M>)>,
+) {
+    for (entity, resource_pool, marker) in query.iter() {
+        let max: f32 = resource_pool.max().into();
+        let current: f32 = resource_pool.current().into();
+        let below = max > 0.0 && current / max < threshold.fraction;
+
+        match (below, marker.is_some()) {
+            (true, false) => {
+                commands.entity(entity).insert(M::default());
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<M>();
+            }
+            _ => (),
         }
     }
 }
@@ -142,52 +2186,56 @@ mod trait_impls {
         fn clone(&self) -> Self {
             Self {
                 current: self.current.clone(),
+                min: self.min.clone(),
                 max: self.max.clone(),
+                base_max: self.base_max.clone(),
+                max_modifiers: self.max_modifiers.clone(),
                 regen_rate: self.regen_rate.clone(),
+                out_of_combat_regen_rate: self.out_of_combat_regen_rate.clone(),
+                out_of_combat_delay: self.out_of_combat_delay,
+                time_out_of_combat: self.time_out_of_combat,
+                soft_cap: self.soft_cap,
+                soft_cap_decay: self.soft_cap_decay,
+                regen_remainder: self.regen_remainder,
+                regen_curve: self.regen_curve,
                 _phantom: self._phantom.clone(),
             }
         }
     }
 
-    impl<R: ResourceType> Copy for ResourcePool<R> {}
-
     impl<R: ResourceType> Add<R> for ResourcePool<R> {
         type Output = ResourcePool<R>;
 
-        fn add(self, rhs: R) -> ResourcePool<R> {
-            ResourcePool {
-                current: self.current + rhs.min(self.max),
-                max: self.max,
-                regen_rate: self.regen_rate,
-                _phantom: PhantomData::default(),
-            }
+        fn add(mut self, rhs: R) -> ResourcePool<R> {
+            self += rhs;
+            self
         }
     }
 
     impl<R: ResourceType> Sub<R> for ResourcePool<R> {
         type Output = ResourcePool<R>;
 
-        fn sub(self, other: R) -> ResourcePool<R> {
-            let difference: R = self.current - other;
-
-            ResourcePool {
-                current: difference.max(R::ZERO),
-                max: self.max,
-                regen_rate: self.regen_rate,
-                _phantom: PhantomData::default(),
-            }
+        fn sub(mut self, other: R) -> ResourcePool<R> {
+            self -= other;
+            self
         }
     }
 
     impl<R: ResourceType> AddAssign<R> for ResourcePool<R> {
         fn add_assign(&mut self, other: R) {
-            *self = *self + other;
+            self.current = (self.current + other).clamp(self.min, self.max);
         }
     }
 
     impl<R: ResourceType> SubAssign<R> for ResourcePool<R> {
         fn sub_assign(&mut self, other: R) {
-            *self = *self - other;
+            // Saturates at the floor, so that pools of unsigned types cannot underflow
+            let difference: R = if other > self.current - self.min {
+                self.min
+            } else {
+                self.current - other
+            };
+            self.current = difference.clamp(self.min, self.max);
         }
     }
 
@@ -202,4 +2250,61 @@ mod trait_impls {
             Some(self.current.cmp(&other))
         }
     }
+
+    impl<R: ResourceType> PartialEq for ResourcePool<R> {
+        fn eq(&self, other: &ResourcePool<R>) -> bool {
+            self.current == other.current
+        }
+    }
+
+    /// As `R` is `Ord`, pools can always be compared
+    impl<R: ResourceType> PartialOrd for ResourcePool<R> {
+        fn partial_cmp(&self, other: &ResourcePool<R>) -> Option<Ordering> {
+            Some(self.current.cmp(&other.current))
+        }
+    }
+}
+
+/// The resource types used by the examples in this crate's documentation
+#[doc(hidden)]
+pub mod doc_resources {
+    use super::ResourceType;
+    use bevy::prelude::*;
+    use derive_more::{Add, Sub};
+
+    macro_rules! doc_resource {
+        ($name:ident($inner:ty), $logical_max:expr) => {
+            #[derive(
+                Component, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Add, Sub, Debug,
+            )]
+            pub struct $name(pub $inner);
+
+            impl ResourceType for $name {
+                const ZERO: Self = $name(0);
+                const LOGICAL_MAX: Self = $name($logical_max);
+            }
+
+            impl From<f32> for $name {
+                fn from(value: f32) -> Self {
+                    $name(value as $inner)
+                }
+            }
+
+            impl From<$name> for f32 {
+                fn from(value: $name) -> f32 {
+                    value.0 as f32
+                }
+            }
+        };
+    }
+
+    doc_resource!(Mana(u32), 1000);
+    // Signed, so that pools with a debt floor can be shown
+    doc_resource!(Life(i32), 1000);
+    // Signed, so that pools which decay over time can be shown
+    doc_resource!(Rage(i32), 100);
+    doc_resource!(Energy(u32), 100);
+    doc_resource!(Overdrive(u32), 100);
+    doc_resource!(Shields(u32), 1000);
+    doc_resource!(ComboPoints(u8), 5);
 }